@@ -10,6 +10,7 @@ export default class AppController {
     return view.render('pages/app/request', {
       pendingApp: pendingApp.serialize(),
       requestedPermissions: await pendingApp.requestedPermissionsArray(),
+      tokenExpiresIn: AppService.tokenExpirationTime(pendingApp).rescale().toHuman(),
     });
   }
 
@@ -35,8 +36,8 @@ export default class AppController {
   }
 
   async apiRequestApp({ request, response }: HttpContext) {
-    const { app_name, requested_permissions } = request.body();
-    const pendingApp = await AppService.createPendingApp(app_name, requested_permissions);
+    const { app_name, requested_permissions, token_expires_in } = request.body();
+    const pendingApp = await AppService.createPendingApp(app_name, requested_permissions, token_expires_in);
     
     return response.created({
       private_code: pendingApp.privateCode,
@@ -67,4 +68,21 @@ export default class AppController {
     await pendingApp.delete();
     return response.ok({ status: 'ok' });
   }
-}
\ No newline at end of file
+
+  async apiCurrentToken({ auth, response }: HttpContext) {
+    const user = auth.getUserOrFail();
+    const token = user.currentAccessToken!;
+
+    return response.ok({
+      username: user.githubUsername,
+      permissions: token.abilities,
+      expires_at: token.expiresAt?.toISOString() ?? null,
+    });
+  }
+
+  async apiRevokeCurrentToken({ auth, response }: HttpContext) {
+    const user = auth.getUserOrFail();
+    await AppService.revokeCurrentAccessToken(user);
+    return response.ok({ status: 'ok' });
+  }
+}
@@ -5,15 +5,19 @@ import { DateTime, Duration } from "luxon";
 import User from "../users/User.js";
 import { cuid } from "@adonisjs/core/helpers";
 
+const DEFAULT_TOKEN_EXPIRATION_TIME = Duration.fromObject({ minutes: 10 });
+
 class AppService {
   async createPendingApp(
     appName: string,
     requestedPermissions: Infer<typeof appPermissionsArraySchema>,
+    tokenExpiresIn: number | null = null,
     expirationTime: Duration = Duration.fromObject({ minutes: 10 }),
   ): Promise<PendingApp> {
     const validated = await pendingAppSchema.validate({ 
       app_name: appName,
-      requested_permissions: requestedPermissions
+      requested_permissions: requestedPermissions,
+      token_expires_in: tokenExpiresIn ?? undefined,
     });
 
     const newPendingApp = await PendingApp.create({
@@ -21,6 +25,7 @@ class AppService {
       privateCode: await this.generatePendingAppCode('private_code'),
       publicCode: await this.generatePendingAppCode('public_code'),
       requestedPermissions: JSON.stringify(validated.requested_permissions),
+      tokenExpiresIn: validated.token_expires_in ?? null,
       expiresAt: DateTime.local().plus(expirationTime),
     });
 
@@ -52,11 +57,25 @@ class AppService {
     return expiredApps.length;
   }
 
+  /**
+   * How long the access token issued for the pending app will be valid.
+   */
+  tokenExpirationTime(
+    pendingApp: PendingApp,
+    defaultExpirationTime: Duration = DEFAULT_TOKEN_EXPIRATION_TIME,
+  ): Duration {
+    return pendingApp.tokenExpiresIn
+      ? Duration.fromObject({ seconds: pendingApp.tokenExpiresIn })
+      : defaultExpirationTime;
+  }
+
   async approvePendingApp(
     pendingApp: PendingApp,
     user: User,
-    expirationTime: Duration = Duration.fromObject({ minutes: 10 }),
+    defaultExpirationTime: Duration = DEFAULT_TOKEN_EXPIRATION_TIME,
   ): Promise<void> {
+    const expirationTime = this.tokenExpirationTime(pendingApp, defaultExpirationTime);
+
     const token = await User.accessTokens.create(
       user,
       await pendingApp.rawRequestedPermissionsArray(),
@@ -71,6 +90,12 @@ class AppService {
     await pendingApp.save();
   }
 
+  async revokeCurrentAccessToken(user: User): Promise<void> {
+    if (user.currentAccessToken) {
+      await User.accessTokens.delete(user, user.currentAccessToken.identifier);
+    }
+  }
+
   async denyPendingApp(pendingApp: PendingApp) {
     pendingApp.status = 'DENIED';
     await pendingApp.save();
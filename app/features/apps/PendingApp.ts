@@ -34,6 +34,12 @@ export default class PendingApp extends BaseModel {
   @column()
   declare status: PendingAppStatus
 
+  /**
+   * Lifetime of the issued access token in seconds, null for the default lifetime.
+   */
+  @column()
+  declare tokenExpiresIn: number|null
+
   @column.dateTime()
   declare expiresAt: DateTime
 
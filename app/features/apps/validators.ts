@@ -18,10 +18,13 @@ export const appPermissionsArraySchema = vine.compile(
   vine.array(appPermission).minLength(1)
 );
 
+export const MAX_TOKEN_EXPIRES_IN_SECONDS = 90 * 24 * 60 * 60; // 90 days
+
 export const pendingAppSchema = vine.compile(
   vine.object({
     app_name: vine.string().minLength(2).maxLength(32),
     requested_permissions: vine.array(appPermission).minLength(1),
+    token_expires_in: vine.number().withoutDecimals().min(60).max(MAX_TOKEN_EXPIRES_IN_SECONDS).optional(),
   }),
 );
 
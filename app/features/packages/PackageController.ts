@@ -144,9 +144,77 @@ export default class PackageController {
         scope: pack.scope.reference,
         name: `${pack.name}@${version!.version}`,
       }),
+      download_url: makeAbsoluteUrl('package.api.download', {
+        scope: pack.scope.reference,
+        name: `${pack.name}@${version!.version}`,
+      }),
+      checksum: version!.artifactChecksum,
+    });
+  }
+
+  async apiPackageVersions({ params, response }: HttpContext) {
+    const { pack } = await PackageResolver
+      .fromScopeAndName(params.scope, decodeURIComponent(params.name))
+      .resolveOrFail();
+
+    await pack.loadOnce('versions');
+    const versions = await Promise.all(
+      pack.versions
+        .filter(version => !version.isYanked)
+        .map(async (version) => ({
+          version: version.version,
+          download_url: makeAbsoluteUrl('package.api.download', {
+            scope: pack.scope.reference,
+            name: `${pack.name}@${version.version}`,
+          }),
+          checksum: version.artifactChecksum,
+          dependencies: await this.getVersionDependencies(version),
+        }))
+    );
+
+    return response.ok({
+      reference: pack.reference,
+      versions,
     });
   }
 
+  async apiDownloadPackage({ params, response }: HttpContext) {
+    const { pack, version } = await this.packageResolverFromParams(params)
+      .expectExactVersion()
+      .expectVersion()
+      .resolveOrFail();
+
+    const artifactKey = version!.artifactFileKey;
+    if (!artifactKey) {
+      throw new errors.E_HTTP_EXCEPTION(
+        `Package ${pack.reference}@${version!.version} has no artifact`,
+        { status: 404 },
+      );
+    }
+
+    const disk = drive.use('fs');
+    response.header('Content-Type', 'application/gzip');
+    response.header(
+      'Content-Disposition',
+      `attachment; filename="${pack.scope.name}-${pack.name}-${version!.version}.tar.gz"`,
+    );
+    response.stream(await disk.getStream(artifactKey));
+  }
+
+  private async getVersionDependencies(version: PackageVersion): Promise<Record<string, string>> {
+    const definitionFile = await version.getFile('boo.json');
+    if (!definitionFile || !definitionFile.fileKey) {
+      return {};
+    }
+
+    const content = await drive.use('fs').get(definitionFile.fileKey);
+    try {
+      return JSON.parse(content).dependencies || {};
+    } catch {
+      return {};
+    }
+  }
+
   private packageResolverFromParams(params: Record<string, any>): PackageResolver {
     let name = params.name;
 
@@ -20,6 +20,9 @@ export default class PackageVersion extends BaseModel {
   @column()
   declare artifactFileKey: string|null
 
+  @column()
+  declare artifactChecksum: string|null
+
   @column()
   declare isYanked: boolean
 
@@ -33,6 +33,8 @@ interface PackagePublishJobFailure {
 
 interface PackagePublishError {
   message: string;
+  code?: string;
+  path?: string;
 }
 
 export type PackagePublishJobResult =
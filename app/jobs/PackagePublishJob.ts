@@ -12,6 +12,7 @@ import db from "@adonisjs/lucid/services/db";
 import PackageVersion from "#features/packages/PackageVersion";
 import PackageVersionFile from "#features/packages/PackageVersionFile";
 import zlib from 'node:zlib';
+import { createHash } from 'node:crypto';
 
 type PackagePublishJobPayload = {
   publishingJobId: number;
@@ -79,9 +80,16 @@ export default class PackagePublishJob extends Job {
       expectedVersion: job.version,
     });
 
-    if (validationResults.length > 0) {
-      this.logger.warn(`Package validation failed for ${job.relatedPackage.reference} v${job.version}:`, validationResults);
-      await job.updateFailed(validationResults);
+    const validationErrors = validationResults.filter((result) => result.severity === 'error');
+    const validationWarnings = validationResults.filter((result) => result.severity === 'warning');
+
+    if (validationWarnings.length > 0) {
+      this.logger.info(`Package validation warnings for ${job.relatedPackage.reference} v${job.version}:`, validationWarnings);
+    }
+
+    if (validationErrors.length > 0) {
+      this.logger.warn(`Package validation failed for ${job.relatedPackage.reference} v${job.version}:`, validationErrors);
+      await job.updateFailed(validationErrors.map(({ code, message, path }) => ({ code, message, path })));
       return false;
     }
 
@@ -104,6 +112,10 @@ export default class PackagePublishJob extends Job {
         await disk.delete(artifactKey)
       });
 
+      const artifactChecksum = createHash('sha256')
+        .update(await disk.getBytes(artifactKey))
+        .digest('hex');
+
       const archiveStream = await disk.getStream(artifactKey);
 
       const trx = await db.transaction();
@@ -116,6 +128,7 @@ export default class PackagePublishJob extends Job {
         packageId: job.relatedPackage.id,
         version: job.version,
         artifactFileKey: artifactKey,
+        artifactChecksum,
       }, { client: trx });
 
       this.logger.info(`Created package version entry ${job.relatedPackage.reference} v${job.version} with ID ${packageVersion.id}`);
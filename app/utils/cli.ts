@@ -6,9 +6,16 @@ type PackageArchiveValidationOptions = {
   expectedVersion?: string;
 };
 
-type PackageArchiveValidationResults = {
+export type PackageArchiveValidationResult = {
+  /** Stable identifier of the result, e.g. `package.too-large`. */
+  code: string;
+  severity: 'error' | 'warning';
   message: string;
-}[];
+  /** Path inside the archive the result relates to. */
+  path?: string;
+};
+
+type PackageArchiveValidationResults = PackageArchiveValidationResult[];
 
 export class CliRunner {
   private static instance: CliRunner | null = null;
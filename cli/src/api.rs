@@ -1,4 +1,6 @@
-use reqwest::{Error, Response, multipart};
+use std::collections::BTreeMap;
+
+use reqwest::{Error, Response, StatusCode, multipart};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use urlencoding;
 
@@ -19,6 +21,33 @@ pub enum ApiRequestError {
     ValidationErrors(ApiErrors),
 }
 
+impl std::fmt::Display for ApiRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiRequestError::ApiError(message) => write!(f, "API error: {}", message),
+            ApiRequestError::AuthError(message) => write!(f, "Authorization error: {}", message),
+            ApiRequestError::NetworkError(err) => {
+                write!(f, "Network error: {}", err)?;
+                if let Some(source) = std::error::Error::source(err) {
+                    write!(f, ": {}", source)?;
+                }
+                Ok(())
+            }
+            ApiRequestError::ValidationErrors(errors) => {
+                let messages = errors
+                    .errors
+                    .iter()
+                    .map(|e| match &e.field {
+                        Some(field) => format!("{}: {}", field, e.message),
+                        None => e.message.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, "API error: {}", messages.join(", "))
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ApiErrors {
     pub errors: Vec<ApiError>,
@@ -34,6 +63,9 @@ pub struct ApiError {
 pub struct AuthRequest {
     pub app_name: String,
     pub requested_permissions: Vec<String>,
+    /// Lifetime of the issued token in seconds, the server default is used when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_expires_in: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -64,6 +96,18 @@ pub struct AuthRequestDeleteResponse {
     status: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccessTokenInfo {
+    pub username: String,
+    pub permissions: Vec<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeTokenResponse {
+    status: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreatePublishJobRequest {
     pub name: String,
@@ -91,7 +135,7 @@ pub struct PublishJobResultErrors {
     pub errors: Vec<ApiError>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PackagePublishJobStatus {
     Pending,
@@ -106,6 +150,31 @@ pub struct ResolvedPackage {
     pub reference: String,
     pub version: String,
     pub info_url: String,
+    pub download_url: String,
+    /// Hex encoded SHA-256 of the package archive. Versions published
+    /// before checksums were recorded do not have one.
+    pub checksum: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageVersions {
+    pub reference: String,
+    pub versions: Vec<PackageVersionInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackageVersionInfo {
+    pub version: String,
+    pub download_url: String,
+    pub checksum: Option<String>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+impl Default for BooApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BooApiClient {
@@ -127,6 +196,12 @@ impl BooApiClient {
         }
     }
 
+    /// The URL of a package's page on the website the API belongs to.
+    pub fn package_page_url(&self, package_name: &str) -> String {
+        let site_url = self.base_url.trim_end_matches('/').trim_end_matches("/api");
+        format!("{}/{}", site_url, package_name)
+    }
+
     pub fn set_access_token(&mut self, token: String) {
         self.access_token = Some(token);
     }
@@ -143,28 +218,10 @@ impl BooApiClient {
     where
         T: DeserializeOwned,
     {
-        let response = response_result.map_err(|e| ApiRequestError::NetworkError(e))?;
+        let response = response_result.map_err(ApiRequestError::NetworkError)?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response
-                .bytes()
-                .await
-                .map_err(ApiRequestError::NetworkError)?;
-
-            if let Ok(api_error) = serde_json::from_slice::<ApiError>(&body) {
-                return Err(ApiRequestError::ApiError(api_error.message));
-            }
-
-            if let Ok(validation_errors) = serde_json::from_slice::<ApiErrors>(&body) {
-                return Err(ApiRequestError::ValidationErrors(validation_errors.clone()));
-            }
-
-            return Err(ApiRequestError::ApiError(format!(
-                "Request failed with status {} and unknown error format: {}",
-                status,
-                String::from_utf8_lossy(&body)
-            )));
+            return Err(Self::parse_error_response(response).await);
         }
 
         let bytes = response
@@ -181,6 +238,44 @@ impl BooApiClient {
         })
     }
 
+    async fn parse_error_response(response: Response) -> ApiRequestError {
+        let status = response.status();
+        let body = match response.bytes().await {
+            Ok(body) => body,
+            Err(e) => return ApiRequestError::NetworkError(e),
+        };
+
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            let message = serde_json::from_slice::<ApiError>(&body)
+                .map(|e| e.message)
+                .or_else(|_| {
+                    serde_json::from_slice::<ApiErrors>(&body).map(|e| {
+                        e.errors
+                            .iter()
+                            .map(|e| e.message.clone())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                })
+                .unwrap_or_else(|_| status.to_string());
+            return ApiRequestError::AuthError(message);
+        }
+
+        if let Ok(api_error) = serde_json::from_slice::<ApiError>(&body) {
+            return ApiRequestError::ApiError(api_error.message);
+        }
+
+        if let Ok(validation_errors) = serde_json::from_slice::<ApiErrors>(&body) {
+            return ApiRequestError::ValidationErrors(validation_errors.clone());
+        }
+
+        ApiRequestError::ApiError(format!(
+            "Request failed with status {} and unknown error format: {}",
+            status,
+            String::from_utf8_lossy(&body)
+        ))
+    }
+
     pub async fn create_auth_request(
         &self,
         request: AuthRequest,
@@ -210,6 +305,34 @@ impl BooApiClient {
         Self::parse_response(response_result).await
     }
 
+    pub async fn get_access_token_info(&self) -> Result<AccessTokenInfo, ApiRequestError> {
+        let url = format!("{}auth/token", self.base_url);
+        let response_result = self
+            .client
+            .get(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.get_access_token()?),
+            )
+            .send()
+            .await;
+        Self::parse_response(response_result).await
+    }
+
+    pub async fn revoke_access_token(&self) -> Result<RevokeTokenResponse, ApiRequestError> {
+        let url = format!("{}auth/token", self.base_url);
+        let response_result = self
+            .client
+            .delete(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.get_access_token()?),
+            )
+            .send()
+            .await;
+        Self::parse_response(response_result).await
+    }
+
     pub async fn create_publishing_job(
         &self,
         request: CreatePublishJobRequest,
@@ -274,13 +397,63 @@ impl BooApiClient {
         &self,
         package_reference: &str,
     ) -> Result<ResolvedPackage, ApiRequestError> {
-        let url = format!(
-            "{}package/{}",
-            self.base_url,
-            package_reference
-        );
+        // Version ranges may contain characters like spaces, commas or '<', so the
+        // name and version part needs to be encoded. The scope is always plain.
+        let url = match package_reference.split_once('/') {
+            Some((scope, name)) => format!(
+                "{}package/{}/{}",
+                self.base_url,
+                scope,
+                urlencoding::encode(name)
+            ),
+            None => format!("{}package/{}", self.base_url, package_reference),
+        };
+
+        let response_result = self.client.get(&url).send().await;
+        Self::parse_response(response_result).await
+    }
 
+    pub async fn get_package_versions(
+        &self,
+        package_name: &str,
+    ) -> Result<PackageVersions, ApiRequestError> {
+        let url = format!("{}package/{}/versions", self.base_url, package_name);
         let response_result = self.client.get(&url).send().await;
         Self::parse_response(response_result).await
     }
+
+    /// Like [`Self::get_package_versions`], but returns `None` for packages that were never published.
+    pub async fn find_package_versions(
+        &self,
+        package_name: &str,
+    ) -> Result<Option<PackageVersions>, ApiRequestError> {
+        let url = format!("{}package/{}/versions", self.base_url, package_name);
+        let response_result = self.client.get(&url).send().await;
+        if let Ok(response) = &response_result
+            && response.status() == StatusCode::NOT_FOUND
+        {
+            return Ok(None);
+        }
+        Self::parse_response(response_result).await.map(Some)
+    }
+
+    pub async fn download_package(&self, download_url: &str) -> Result<Vec<u8>, ApiRequestError> {
+        let response = self
+            .client
+            .get(download_url)
+            .send()
+            .await
+            .map_err(ApiRequestError::NetworkError)?;
+
+        if !response.status().is_success() {
+            return Err(Self::parse_error_response(response).await);
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(ApiRequestError::NetworkError)?;
+
+        Ok(bytes.to_vec())
+    }
 }
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use owo_colors::OwoColorize;
+use tokio::time::sleep;
+
+use crate::{
+    api::{ApiRequestError, AuthRequest, AuthRequestResponse, AuthRequestStatus, BooApiClient},
+    output, print_success,
+};
+
+const POLLING_INTERVAL_SECS: u64 = 1;
+const AUTH_TIMEOUT_SECS: u64 = 300; // 5 minutes
+
+pub enum AuthFlowError {
+    Denied,
+    Api(ApiRequestError),
+}
+
+impl From<ApiRequestError> for AuthFlowError {
+    fn from(err: ApiRequestError) -> Self {
+        AuthFlowError::Api(err)
+    }
+}
+
+/// Asks the user to approve the application in the browser and waits for the access token.
+pub async fn request_access_token(
+    client: &BooApiClient,
+    requested_permissions: Vec<String>,
+    token_expires_in: Option<u64>,
+) -> Result<String, AuthFlowError> {
+    let auth_request = AuthRequest {
+        app_name: client.app_name.clone(),
+        requested_permissions,
+        token_expires_in,
+    };
+
+    let auth_request_response = client.create_auth_request(auth_request).await?;
+
+    if output::is_json() {
+        output::emit(
+            "authorization_requested",
+            serde_json::json!({ "request_url": auth_request_response.request_url }),
+        );
+    } else {
+        println!();
+        println!("Please approve the application to act on your behalf:");
+        println!("- {}", auth_request_response.request_url.underline());
+        println!();
+        println!("{}", "Waiting for approval...".dimmed());
+    }
+
+    let access_token = run_auth_verification_loop(client, &auth_request_response).await;
+
+    client
+        .delete_auth_request(&auth_request_response.private_code)
+        .await?;
+
+    let access_token = access_token?;
+    print_success("Authorization approved");
+
+    Ok(access_token)
+}
+
+async fn run_auth_verification_loop(
+    client: &BooApiClient,
+    auth_request_response: &AuthRequestResponse,
+) -> Result<String, AuthFlowError> {
+    let start_time = std::time::Instant::now();
+
+    loop {
+        if start_time.elapsed().as_secs() > AUTH_TIMEOUT_SECS {
+            return Err(AuthFlowError::Api(ApiRequestError::AuthError(
+                "Authorization request timed out".to_string(),
+            )));
+        }
+
+        sleep(Duration::from_secs(POLLING_INTERVAL_SECS)).await;
+
+        let request_status = client
+            .get_auth_request_status(&auth_request_response.private_code)
+            .await?;
+
+        match &request_status.status {
+            AuthRequestStatus::Pending => {
+                continue;
+            }
+            AuthRequestStatus::Approved => match request_status.access_token {
+                Some(token) => return Ok(token),
+                None => {
+                    return Err(AuthFlowError::Api(ApiRequestError::ApiError(
+                        "No access token provided in the approval response.".to_string(),
+                    )));
+                }
+            },
+            AuthRequestStatus::Denied => return Err(AuthFlowError::Denied),
+        }
+    }
+}
+
+/// Checks whether a granted permission covers the requested one, following the
+/// server's rules: `package.upload-new-version:@scope` covers every package in the
+/// scope, `@scope/name` every version of the package and `@scope/name@1.0.0` only
+/// that exact version.
+pub fn permission_covers(granted: &str, requested: &str) -> bool {
+    const UPLOAD_PERMISSION: &str = "package.upload-new-version:";
+
+    let (Some(granted), Some(requested)) = (
+        granted.strip_prefix(UPLOAD_PERMISSION),
+        requested.strip_prefix(UPLOAD_PERMISSION),
+    ) else {
+        return granted == requested;
+    };
+
+    let (granted_scope, granted_rest) = granted.split_once('/').unwrap_or((granted, ""));
+    let (requested_scope, requested_rest) = requested.split_once('/').unwrap_or((requested, ""));
+
+    if granted_scope != requested_scope {
+        return false;
+    }
+
+    if granted_rest.is_empty() {
+        return true;
+    }
+
+    let (granted_name, granted_version) = split_version(granted_rest);
+    let (requested_name, requested_version) = split_version(requested_rest);
+
+    granted_name == requested_name
+        && granted_version.is_none_or(|version| requested_version == Some(version))
+}
+
+fn split_version(name: &str) -> (&str, Option<&str>) {
+    match name.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (name, None),
+    }
+}
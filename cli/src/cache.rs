@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use tar::Archive;
+
+const CACHE_DIR_ENV: &str = "BOO_CACHE_DIR";
+
+/// Per-user directory where downloaded packages are unpacked.
+///
+/// Packages are stored as `<root>/<scope>/<name>/<version>/`, so the
+/// contents of `@foo/bar@1.0.0` end up in `<root>/foo/bar/1.0.0/lib.ua`.
+pub struct PackageCache {
+    root: PathBuf,
+}
+
+impl PackageCache {
+    pub fn new() -> Result<Self, String> {
+        if let Ok(dir) = std::env::var(CACHE_DIR_ENV) {
+            return Ok(PackageCache {
+                root: PathBuf::from(dir),
+            });
+        }
+
+        let cache_dir = dirs::cache_dir().ok_or_else(|| {
+            format!(
+                "Could not determine the cache directory, please set {} environment variable.",
+                CACHE_DIR_ENV
+            )
+        })?;
+
+        Ok(PackageCache {
+            root: cache_dir.join("boo").join("packages"),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn package_path(&self, name: &str, version: &str) -> PathBuf {
+        let clean_name = name.strip_prefix('@').unwrap_or(name);
+        self.root.join(clean_name).join(version)
+    }
+
+    fn checksum_path(&self, name: &str, version: &str) -> PathBuf {
+        self.package_path(name, version)
+            .with_file_name(format!("{}.sha256", version))
+    }
+
+    pub fn contains(&self, name: &str, version: &str) -> bool {
+        self.package_path(name, version).is_dir()
+    }
+
+    /// The checksum of the archive the cached package was unpacked from, if known.
+    pub fn checksum(&self, name: &str, version: &str) -> Option<String> {
+        fs::read_to_string(self.checksum_path(name, version))
+            .ok()
+            .map(|checksum| checksum.trim().to_string())
+    }
+
+    /// Unpacks a package archive into the cache, replacing any previous contents.
+    /// The archive is first extracted into a temporary sibling directory, so an
+    /// interrupted install never leaves a half-populated package behind.
+    pub fn store(
+        &self,
+        name: &str,
+        version: &str,
+        archive: &[u8],
+        checksum: &str,
+    ) -> Result<PathBuf, String> {
+        let target = self.package_path(name, version);
+        let parent = target
+            .parent()
+            .ok_or_else(|| format!("Invalid cache path '{}'", target.display()))?;
+
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create cache directory '{}': {}", parent.display(), e))?;
+
+        let staging = parent.join(format!(".{}.partial", version));
+        if staging.exists() {
+            fs::remove_dir_all(&staging).map_err(|e| {
+                format!("Failed to clean up '{}': {}", staging.display(), e)
+            })?;
+        }
+
+        Archive::new(GzDecoder::new(archive))
+            .unpack(&staging)
+            .map_err(|e| format!("Failed to unpack {}@{}: {}", name, version, e))?;
+
+        if target.exists() {
+            fs::remove_dir_all(&target)
+                .map_err(|e| format!("Failed to replace '{}': {}", target.display(), e))?;
+        }
+
+        fs::rename(&staging, &target)
+            .map_err(|e| format!("Failed to move package into '{}': {}", target.display(), e))?;
+
+        let checksum_path = self.checksum_path(name, version);
+        fs::write(&checksum_path, checksum)
+            .map_err(|e| format!("Failed to write '{}': {}", checksum_path.display(), e))?;
+
+        Ok(target)
+    }
+}
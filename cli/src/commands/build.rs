@@ -0,0 +1,255 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::{
+    BuildArgs, RunArgs,
+    cache::PackageCache,
+    common::{PackageReference, normalize_path},
+    imports::find_imports,
+    lockfile::{LOCKFILE_NAME, Lockfile},
+    output, print_error, print_success,
+};
+
+/// Directory inside the build output where dependencies are vendored.
+const VENDOR_DIR: &str = "boo_modules";
+
+/// Written into the output directory to mark it as safe to remove on the next build.
+const BUILD_MARKER: &str = ".boo-build";
+
+pub(crate) fn run_build(args: BuildArgs) {
+    let entry = build_project(&args).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    output::emit("built", serde_json::json!({ "entry": entry }));
+    print_success(&format!("Project built into '{}'.", entry.display()));
+}
+
+pub(crate) fn run_run(args: RunArgs) {
+    let entry = build_project(&args.build).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let status = process::Command::new("uiua")
+        .arg("run")
+        .arg(&entry)
+        .args(&args.args)
+        .status()
+        .unwrap_or_else(|e| {
+            print_error(&format!("Failed to run 'uiua': {}", e));
+            process::exit(1);
+        });
+
+    process::exit(status.code().unwrap_or(1));
+}
+
+/// A source file to copy into the build output.
+struct SourceFile {
+    /// Where the file is read from.
+    source_path: PathBuf,
+    /// Where the file is written to, relative to the output directory.
+    output_path: PathBuf,
+}
+
+/// Copies the entry file and everything it imports into the output directory.
+/// Relative imports keep their layout, while `boo:` imports are vendored under
+/// `boo_modules/` and rewritten to relative paths, so a stock Uiua interpreter
+/// can run the result. Returns the path of the built entry file.
+fn build_project(args: &BuildArgs) -> Result<PathBuf, String> {
+    let entry = normalize_path(Path::new(&args.entry))
+        .ok_or_else(|| format!("Entry file '{}' must be inside the project.", args.entry))?;
+
+    if !entry.is_file() {
+        return Err(format!("Entry file '{}' not found.", args.entry));
+    }
+
+    let out_dir = PathBuf::from(&args.out_dir);
+    clean_out_dir(&out_dir, &entry)?;
+
+    let lockfile = Lockfile::read_from_file(Path::new(LOCKFILE_NAME))?;
+    let cache = PackageCache::new()?;
+
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([SourceFile {
+        source_path: entry.clone(),
+        output_path: entry.clone(),
+    }]);
+
+    while let Some(file) = queue.pop_front() {
+        if !visited.insert(file.output_path.clone()) {
+            continue;
+        }
+
+        let source = fs::read_to_string(&file.source_path)
+            .map_err(|e| format!("Failed to read '{}': {}", file.source_path.display(), e))?;
+
+        let source_dir = file.source_path.parent().unwrap_or(Path::new(""));
+        let output_dir = file.output_path.parent().unwrap_or(Path::new(""));
+
+        let mut rewritten = String::with_capacity(source.len());
+        let mut last_index = 0;
+
+        for import in find_imports(&source) {
+            let location = format!("{}:{}", file.source_path.display(), import.line);
+
+            if let Some(reference) = import.boo_reference() {
+                let dependency = resolve_dependency(reference, lockfile.as_ref(), &cache)
+                    .map_err(|e| format!("{} ({})", e, location))?;
+
+                let import_path = relative_import_path(output_dir, &dependency.output_path);
+                rewritten.push_str(&source[last_index..import.span.start]);
+                rewritten.push_str(&format!("\"{}\"", import_path));
+                last_index = import.span.end;
+
+                queue.push_back(dependency);
+                continue;
+            }
+
+            // Imports like `git:` are left for Uiua to handle.
+            if import.path.contains(':') || Path::new(&import.path).is_absolute() {
+                continue;
+            }
+
+            let output_path = normalize_path(&output_dir.join(&import.path)).ok_or_else(|| {
+                format!("Import '{}' points outside of the project ({})", import.path, location)
+            })?;
+
+            let source_path = source_dir.join(&import.path);
+            if !source_path.is_file() {
+                return Err(format!("Imported file '{}' not found ({})", import.path, location));
+            }
+
+            queue.push_back(SourceFile {
+                source_path,
+                output_path,
+            });
+        }
+
+        rewritten.push_str(&source[last_index..]);
+
+        let target = out_dir.join(&file.output_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+
+        fs::write(&target, rewritten)
+            .map_err(|e| format!("Failed to write '{}': {}", target.display(), e))?;
+    }
+
+    let marker = out_dir.join(BUILD_MARKER);
+    fs::write(&marker, "")
+        .map_err(|e| format!("Failed to write '{}': {}", marker.display(), e))?;
+
+    Ok(out_dir.join(entry))
+}
+
+/// Removes the output of a previous build. Only directories holding the build marker
+/// are removed, so pointing `--out-dir` at the project or any other existing directory
+/// can not delete it. Empty directories are built into as they are.
+fn clean_out_dir(out_dir: &Path, entry: &Path) -> Result<(), String> {
+    if !out_dir.exists() {
+        return Ok(());
+    }
+
+    let canonical = |path: &Path| {
+        fs::canonicalize(path)
+            .map_err(|e| format!("Failed to resolve '{}': {}", path.display(), e))
+    };
+    let out_dir_path = canonical(out_dir)?;
+    if canonical(Path::new("."))?.starts_with(&out_dir_path)
+        || canonical(entry)?.starts_with(&out_dir_path)
+    {
+        return Err(format!(
+            "The output directory '{}' contains the project, please choose another one.",
+            out_dir.display()
+        ));
+    }
+
+    let is_empty = fs::read_dir(out_dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+    if is_empty {
+        return Ok(());
+    }
+
+    if !out_dir.join(BUILD_MARKER).is_file() {
+        return Err(format!(
+            "The output directory '{}' was not created by `boo build`, please remove it or choose another one.",
+            out_dir.display()
+        ));
+    }
+
+    fs::remove_dir_all(out_dir)
+        .map_err(|e| format!("Failed to clean '{}': {}", out_dir.display(), e))
+}
+
+/// Finds the installed version of a `boo:` import and returns its `lib.ua`.
+fn resolve_dependency(
+    reference: &str,
+    lockfile: Option<&Lockfile>,
+    cache: &PackageCache,
+) -> Result<SourceFile, String> {
+    let reference = PackageReference::parse(reference)
+        .map_err(|e| format!("Invalid import 'boo:{}': {}", reference, e))?;
+
+    let locked = lockfile
+        .and_then(|lockfile| lockfile.packages.get(&reference.name))
+        .filter(|locked| reference.matches(&locked.version))
+        .ok_or_else(|| {
+            format!(
+                "Package {} is not installed, please run `boo install` first",
+                reference
+            )
+        })?;
+
+    if !cache.contains(&reference.name, &locked.version) {
+        return Err(format!(
+            "Package {}@{} is missing from the cache, please run `boo install` first",
+            reference.name, locked.version
+        ));
+    }
+
+    let source_path = cache
+        .package_path(&reference.name, &locked.version)
+        .join("lib.ua");
+
+    if !source_path.is_file() {
+        return Err(format!(
+            "Package {}@{} does not have a 'lib.ua' file and cannot be imported",
+            reference.name, locked.version
+        ));
+    }
+
+    let clean_name = reference.name.strip_prefix('@').unwrap_or(&reference.name);
+    Ok(SourceFile {
+        source_path,
+        output_path: Path::new(VENDOR_DIR)
+            .join(clean_name)
+            .join(&locked.version)
+            .join("lib.ua"),
+    })
+}
+
+/// The path to use in an import in `from_dir` to reach `to`, using forward slashes.
+fn relative_import_path(from_dir: &Path, to: &Path) -> String {
+    let from = from_dir.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+
+    parts.join("/")
+}
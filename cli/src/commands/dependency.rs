@@ -0,0 +1,133 @@
+use std::{fs, path::Path, process};
+
+use crate::{
+    AddArgs, RemoveArgs,
+    api::BooApiClient,
+    common::{BooPackageDefinition, PackageReference, VersionRange},
+    imports::import_identifier,
+    json_edit, output, print_error, print_success,
+};
+
+use super::validate;
+
+const DEPENDENCIES_KEY: &str = "dependencies";
+
+pub(crate) fn run_add(args: AddArgs) {
+    let definition_path = Path::new("boo.json");
+    let package = BooPackageDefinition::read_from_file(definition_path).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let reference = parse_dependency_reference(&args.package).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    if reference.name == package.name {
+        print_error(&format!("Package '{}' cannot depend on itself.", package.name));
+        process::exit(1);
+    }
+
+    if let Some(version) = &reference.version
+        && let Err(e) = VersionRange::parse(version)
+    {
+        print_error(&format!("Invalid version range '{}': {}", version, e));
+        process::exit(1);
+    }
+
+    let client = BooApiClient::new();
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let resolved = rt
+        .block_on(client.resolve_package(&reference.to_string()))
+        .unwrap_or_else(|e| {
+            print_error(&format!("Failed to resolve {}: {}", reference, e));
+            process::exit(1);
+        });
+
+    let version_range = reference
+        .version
+        .clone()
+        .unwrap_or_else(|| format!("^{}", resolved.version));
+
+    let contents = fs::read_to_string(definition_path).unwrap_or_else(|e| {
+        print_error(&format!("Failed to read boo.json: {}", e));
+        process::exit(1);
+    });
+
+    let updated = json_edit::set_nested_string(&contents, DEPENDENCIES_KEY, &reference.name, &version_range)
+        .unwrap_or_else(|e| {
+            print_error(&format!("Failed to update boo.json: {}", e));
+            process::exit(1);
+        });
+
+    if let Err(e) = fs::write(definition_path, updated) {
+        print_error(&format!("Failed to write to boo.json: {}", e));
+        process::exit(1);
+    }
+
+    let import = format!(
+        "{} ~ \"boo:{}\"",
+        import_identifier(&reference.name),
+        reference.name
+    );
+
+    if output::is_json() {
+        return output::emit(
+            "dependency_added",
+            serde_json::json!({
+                "name": reference.name,
+                "range": version_range,
+                "version": resolved.version,
+                "import": import,
+            }),
+        );
+    }
+
+    print_success(&format!(
+        "Added {} {} (currently {}) to boo.json.",
+        reference.name, version_range, resolved.version
+    ));
+    println!();
+    println!("Import it with:");
+    println!("  {}", import);
+    println!();
+    println!("Run `boo install` to download it.");
+}
+
+pub(crate) fn run_remove(args: RemoveArgs) {
+    let definition_path = Path::new("boo.json");
+    let contents = fs::read_to_string(definition_path).unwrap_or_else(|e| {
+        print_error(&format!("Failed to read boo.json: {}", e));
+        process::exit(1);
+    });
+
+    let updated = json_edit::remove_nested_key(&contents, DEPENDENCIES_KEY, &args.package)
+        .unwrap_or_else(|e| {
+            print_error(&format!("Failed to update boo.json: {}", e));
+            process::exit(1);
+        });
+
+    let Some(updated) = updated else {
+        print_error(&format!("'{}' is not a dependency of this package.", args.package));
+        process::exit(1);
+    };
+
+    if let Err(e) = fs::write(definition_path, updated) {
+        print_error(&format!("Failed to write to boo.json: {}", e));
+        process::exit(1);
+    }
+
+    output::emit("dependency_removed", serde_json::json!({ "name": args.package }));
+    print_success(&format!("Removed {} from boo.json.", args.package));
+}
+
+fn parse_dependency_reference(reference: &str) -> Result<PackageReference, String> {
+    let reference = PackageReference::parse(reference)?;
+
+    validate::validate_package_name(&reference.name).map_err(|e| {
+        format!("Invalid package name '{}': {}", reference.name, e.message)
+    })?;
+
+    Ok(reference)
+}
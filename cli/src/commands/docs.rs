@@ -0,0 +1,360 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use pulldown_cmark::{Parser, html};
+use tiny_http::{Header, Response, Server};
+
+use crate::{
+    DocsArgs,
+    api::BooApiClient,
+    commands::publish,
+    common::{BooPackageDefinition, PackageReference, normalize_path},
+    exclude::{BOOIGNORE_FILE, GITIGNORE_FILE},
+    exports::{Binding, BindingKind, PackageApi},
+    imports::BOO_IMPORT_PREFIX,
+    output, print_error, print_success, print_warning,
+};
+
+const STYLE: &str = "
+body { margin: 0; font-family: system-ui, sans-serif; line-height: 1.5; color: #222; }
+header { padding: 1.5rem 2rem; background: #f4f1fa; border-bottom: 1px solid #ddd; }
+header h1 { display: inline; margin: 0 0.75rem 0 0; }
+.layout { display: flex; gap: 2rem; padding: 0 2rem; }
+nav { flex: 0 0 14rem; padding-top: 1rem; }
+nav ul { list-style: none; padding: 0; }
+main { flex: 1; min-width: 0; padding-bottom: 3rem; }
+code, pre { font-family: 'Uiua386', 'DejaVu Sans Mono', monospace; }
+pre { background: #f6f6f6; padding: 0.75rem; overflow-x: auto; }
+a { color: #5a3fc0; }
+article { border-top: 1px solid #eee; padding: 0.5rem 0; }
+.signature, .kind, .version { color: #777; font-weight: normal; }
+";
+
+/// Polled by the served page, which reloads itself once the generation changes.
+const GENERATION_PATH: &str = "/__boo/generation";
+
+const RELOAD_SCRIPT: &str = "<script>
+const generation = '{generation}';
+setInterval(async () => {
+  try {
+    const response = await fetch('{path}');
+    if ((await response.text()) !== generation) location.reload();
+  } catch {}
+}, 1000);
+</script>
+";
+
+pub(crate) fn run_docs(args: DocsArgs) {
+    let out_dir = PathBuf::from(&args.out_dir);
+    let index = generate_docs(&out_dir).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    output::emit("docs_generated", serde_json::json!({ "path": index }));
+    print_success(&format!("Documentation generated into '{}'.", index.display()));
+
+    if args.serve
+        && let Err(e) = serve_docs(out_dir, args.port)
+    {
+        print_error(&e);
+        process::exit(1);
+    }
+}
+
+/// Serves the generated documentation and regenerates it whenever the sources, the
+/// README, boo.json or ignore files change. Open pages reload themselves after each
+/// regeneration.
+fn serve_docs(out_dir: PathBuf, port: u16) -> Result<(), String> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to start the server on port {}: {}", port, e))?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)
+        .map_err(|e| format!("Failed to watch the package for changes: {}", e))?;
+    watcher
+        .watch(Path::new("."), RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch the package for changes: {}", e))?;
+
+    let generation = Arc::new(AtomicU64::new(0));
+    {
+        let generation = generation.clone();
+        let out_dir = out_dir.clone();
+        thread::spawn(move || {
+            while let Ok(event) = receiver.recv() {
+                if !is_package_change(&event) {
+                    continue;
+                }
+
+                // Editors often touch a file several times when saving it.
+                thread::sleep(Duration::from_millis(100));
+                while receiver.try_recv().is_ok() {}
+
+                match generate_docs(&out_dir) {
+                    Ok(_) => {
+                        generation.fetch_add(1, Ordering::Relaxed);
+                        print_success("Documentation regenerated.");
+                    }
+                    Err(e) => print_error(&e),
+                }
+            }
+        });
+    }
+
+    let url = format!("http://127.0.0.1:{}/", port);
+    output::emit("docs_served", serde_json::json!({ "url": url }));
+    print_success(&format!("Serving documentation at {}, press Ctrl+C to stop.", url));
+
+    for request in server.incoming_requests() {
+        let generation = generation.load(Ordering::Relaxed).to_string();
+        let response = match request.url() {
+            GENERATION_PATH => Response::from_string(generation),
+            "/" | "/index.html" => match fs::read_to_string(out_dir.join("index.html")) {
+                Ok(page) => {
+                    let script = RELOAD_SCRIPT
+                        .replace("{generation}", &generation)
+                        .replace("{path}", GENERATION_PATH);
+                    Response::from_string(page.replace("</body>", &format!("{}</body>", script)))
+                        .with_header(
+                            Header::from_bytes("Content-Type", "text/html; charset=utf-8")
+                                .expect("Header is valid"),
+                        )
+                }
+                Err(e) => Response::from_string(format!("Failed to read the documentation: {}", e))
+                    .with_status_code(500),
+            },
+            _ => Response::from_string("Not found").with_status_code(404),
+        };
+        let _ = request.respond(response);
+    }
+
+    Ok(())
+}
+
+fn is_package_change(event: &notify::Result<Event>) -> bool {
+    let Ok(event) = event else {
+        return false;
+    };
+
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.extension().is_some_and(|extension| extension == "ua")
+            || file_name.eq_ignore_ascii_case("README.md")
+            || file_name == "boo.json"
+            || file_name == BOOIGNORE_FILE
+            || file_name == GITIGNORE_FILE
+    })
+}
+
+/// Renders the README and the public API of the current package into a single
+/// self-contained `index.html` inside `out_dir`.
+///
+/// Only files matched by the `include` patterns are used, so the documentation shows
+/// exactly what would be published.
+pub(crate) fn generate_docs(out_dir: &Path) -> Result<PathBuf, String> {
+    let package_data = publish::get_current_package()?;
+    let files = package_data
+        .files
+        .iter()
+        .filter_map(|file| normalize_path(file))
+        .collect::<Vec<_>>();
+
+    let readme = files
+        .iter()
+        .find(|file| file.to_string_lossy().eq_ignore_ascii_case("README.md"))
+        .map(|file| {
+            fs::read_to_string(file)
+                .map_err(|e| format!("Failed to read '{}': {}", file.display(), e))
+        })
+        .transpose()?;
+
+    let api = match files.iter().any(|file| file == Path::new("lib.ua")) {
+        true => {
+            let source = fs::read_to_string("lib.ua")
+                .map_err(|e| format!("Failed to read 'lib.ua': {}", e))?;
+            PackageApi::from_source(&source)
+        }
+        false => {
+            print_warning("'lib.ua' is not included in the package, no API is documented.");
+            PackageApi::default()
+        }
+    };
+
+    let page = render_page(&package_data.package, readme.as_deref(), &api);
+
+    fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create '{}': {}", out_dir.display(), e))?;
+    let index = out_dir.join("index.html");
+    fs::write(&index, page).map_err(|e| format!("Failed to write '{}': {}", index.display(), e))?;
+
+    Ok(index)
+}
+
+fn render_page(package: &BooPackageDefinition, readme: Option<&str>, api: &PackageApi) -> String {
+    let client = BooApiClient::new();
+    let title = format!("{} {}", package.name, package.version);
+
+    let mut page = String::new();
+    let _ = write!(
+        page,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape_html(&title),
+        STYLE
+    );
+    let _ = write!(
+        page,
+        "<header><h1>{}</h1><span class=\"version\">{}</span></header>\n<div class=\"layout\">\n",
+        escape_html(&package.name),
+        escape_html(&package.version)
+    );
+
+    page.push_str("<nav>\n");
+    if readme.is_some() {
+        page.push_str("<a href=\"#readme\">README</a>\n");
+    }
+    if !api.bindings.is_empty() {
+        page.push_str("<h3><a href=\"#api\">API</a></h3>\n<ul>\n");
+        for binding in &api.bindings {
+            let _ = writeln!(
+                page,
+                "<li><a href=\"#{}\"><code>{}</code></a></li>",
+                escape_html(&binding_anchor(binding)),
+                escape_html(&binding.name)
+            );
+        }
+        page.push_str("</ul>\n");
+    }
+    if !package.dependencies.is_empty() {
+        page.push_str("<h3><a href=\"#dependencies\">Dependencies</a></h3>\n");
+    }
+    page.push_str("</nav>\n<main>\n");
+
+    if let Some(readme) = readme {
+        let _ = writeln!(page, "<section id=\"readme\">\n{}</section>", render_markdown(readme));
+    }
+
+    if !api.bindings.is_empty() {
+        page.push_str("<section id=\"api\">\n<h2>API</h2>\n");
+        for binding in &api.bindings {
+            render_binding(&mut page, binding, package, &client);
+        }
+        page.push_str("</section>\n");
+    }
+
+    if !package.dependencies.is_empty() {
+        page.push_str("<section id=\"dependencies\">\n<h2>Dependencies</h2>\n<ul>\n");
+        for (name, range) in &package.dependencies {
+            let _ = writeln!(
+                page,
+                "<li><a href=\"{}\">{}</a> <code>{}</code></li>",
+                escape_html(&client.package_page_url(name)),
+                escape_html(name),
+                escape_html(range)
+            );
+        }
+        page.push_str("</ul>\n</section>\n");
+    }
+
+    page.push_str("</main>\n</div>\n</body>\n</html>\n");
+    page
+}
+
+fn render_binding(
+    page: &mut String,
+    binding: &Binding,
+    package: &BooPackageDefinition,
+    client: &BooApiClient,
+) {
+    let kind = match binding.kind {
+        BindingKind::Binding => "",
+        BindingKind::Macro => "macro",
+        BindingKind::Module => "module",
+        BindingKind::Data => "data",
+    };
+
+    let _ = write!(
+        page,
+        "<article id=\"{}\">\n<h3><code>{}</code>",
+        escape_html(&binding_anchor(binding)),
+        escape_html(&binding.name)
+    );
+    if let Some(signature) = binding.signature {
+        let _ = write!(page, " <span class=\"signature\">{}</span>", signature);
+    }
+    if !kind.is_empty() {
+        let _ = write!(page, " <span class=\"kind\">{}</span>", kind);
+    }
+    page.push_str("</h3>\n");
+
+    if let Some(import) = &binding.import {
+        let dependency = import
+            .strip_prefix(BOO_IMPORT_PREFIX)
+            .and_then(|reference| PackageReference::parse(reference).ok())
+            .filter(|reference| package.dependencies.contains_key(&reference.name));
+
+        match dependency {
+            Some(reference) => {
+                let _ = writeln!(
+                    page,
+                    "<p>Re-exports <a href=\"{}\">{}</a>.</p>",
+                    escape_html(&client.package_page_url(&reference.name)),
+                    escape_html(&reference.name)
+                );
+            }
+            None => {
+                let _ = writeln!(page, "<p>Imports <code>{}</code>.</p>", escape_html(import));
+            }
+        }
+    }
+
+    if let Some(doc) = &binding.doc {
+        page.push_str(&render_markdown(doc));
+    }
+    page.push_str("</article>\n");
+}
+
+fn binding_anchor(binding: &Binding) -> String {
+    format!("api-{}", binding.name)
+}
+
+fn render_markdown(markdown: &str) -> String {
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, Parser::new(markdown));
+    rendered
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,48 @@
+use std::process;
+
+use owo_colors::OwoColorize;
+
+use crate::{ExportsArgs, exports::PackageApi, output, print_error, print_warning};
+
+pub(crate) fn run_exports(args: ExportsArgs) {
+    let api = match &args.package_file {
+        Some(package_file) => read_archive_api(package_file),
+        None => std::fs::read_to_string("lib.ua")
+            .map(|source| PackageApi::from_source(&source))
+            .map_err(|e| format!("Failed to read 'lib.ua': {}", e)),
+    };
+
+    let api = api.unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    if output::is_json() {
+        return output::emit("exports", api);
+    }
+
+    if api.bindings.is_empty() {
+        print_warning("'lib.ua' does not export any bindings.");
+        return;
+    }
+
+    for binding in &api.bindings {
+        match binding.signature {
+            Some(signature) => println!("{} {}", binding.name.bold(), signature.dimmed()),
+            None => println!("{}", binding.name.bold()),
+        }
+        if let Some(doc) = &binding.doc {
+            for line in doc.lines() {
+                println!("  {}", line);
+            }
+        }
+    }
+}
+
+fn read_archive_api(package_file: &str) -> Result<PackageApi, String> {
+    let buffer = std::fs::read(package_file)
+        .map_err(|e| format!("Failed to read package file '{}': {}", package_file, e))?;
+
+    PackageApi::from_archive(&buffer)?
+        .ok_or_else(|| "The package does not contain a 'lib.ua'.".to_string())
+}
@@ -1,10 +1,11 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::InitArgs;
 use crate::common::{BooPackageDefinition};
 use crate::commands::validate;
 
-use crate::{print_error, print_success};
+use crate::{output, print_error, print_success};
 
 pub(crate) fn run_init(args: InitArgs) {
     let file = Path::new("boo.json");
@@ -31,6 +32,9 @@ pub(crate) fn run_init(args: InitArgs) {
             "README.md".to_string(),
             "LICENSE".to_string(),
         ],
+        exclude: vec![],
+        gitignore: false,
+        dependencies: BTreeMap::new(),
     };
 
     let json = serde_json::to_string_pretty(&default_package).unwrap();
@@ -39,5 +43,6 @@ pub(crate) fn run_init(args: InitArgs) {
         return;
     }
 
+    output::emit("initialized", &default_package);
     print_success("boo.json created successfully.");
 }
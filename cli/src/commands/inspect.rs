@@ -0,0 +1,176 @@
+use std::{fs, process};
+
+use bytesize::ByteSize;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use serde_json::json;
+use tar::EntryType;
+use virtual_filesystem::tar_fs::TarFS;
+
+use crate::{InspectArgs, common::sha256_hex, output, print_error, print_warning};
+
+use super::{
+    publish::{self, EXIT_VALIDATION_FAILED},
+    validate::{self, ValidationRules, codes},
+};
+
+const ENTRY_POINTS: &[&str] = &["lib.ua", "main.ua"];
+
+/// Entry errors after which the archive is not loaded, as it may not fit into memory.
+const LIMIT_CODES: &[&str] = &[
+    codes::PACKAGE_TOO_MANY_ENTRIES,
+    codes::PACKAGE_UNCOMPRESSED_TOO_LARGE,
+    codes::PACKAGE_UNREADABLE,
+];
+
+/// An entry of the archive as stored in its tar header.
+#[derive(Debug, Serialize)]
+struct ArchiveEntry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    size: u64,
+}
+
+pub(crate) fn run_inspect(args: InspectArgs) {
+    let buffer = fs::read(&args.package_file).unwrap_or_else(|e| {
+        print_error(&format!(
+            "Failed to read package file '{}': {}",
+            args.package_file, e
+        ));
+        process::exit(1);
+    });
+
+    // The entry headers are checked before any contents are loaded.
+    let entry_issues = validate::validate_archive_entries(&buffer, &ValidationRules::new());
+    if entry_issues
+        .iter()
+        .any(|issue| LIMIT_CODES.contains(&issue.code.as_str()))
+    {
+        for issue in &entry_issues {
+            publish::report_issue(issue);
+        }
+        process::exit(EXIT_VALIDATION_FAILED);
+    }
+
+    let entries = read_entries(&buffer).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let manifest = TarFS::new(flate2::read::GzDecoder::new(buffer.as_slice()))
+        .map_err(|e| format!("Failed to read the package: {}", e))
+        .and_then(|mut package| {
+            validate::try_get_package_definition(&mut package, "boo.json")
+                .map_err(|error| error.message)
+        });
+
+    let entry_points = ENTRY_POINTS
+        .iter()
+        .filter(|entry_point| {
+            entries
+                .iter()
+                .any(|entry| entry.kind == "file" && entry.path == **entry_point)
+        })
+        .copied()
+        .collect::<Vec<_>>();
+
+    let checksum = sha256_hex(&buffer);
+    let size = entries.iter().map(|entry| entry.size).sum::<u64>();
+
+    if output::is_json() {
+        let (manifest, manifest_error) = match manifest {
+            Ok(manifest) => (Some(manifest), None),
+            Err(e) => (None, Some(e)),
+        };
+        return output::emit(
+            "inspect",
+            json!({
+                "path": args.package_file,
+                "sha256": checksum,
+                "compressed_size": buffer.len(),
+                "size": size,
+                "manifest": manifest,
+                "manifest_error": manifest_error,
+                "entry_points": entry_points,
+                "entries": entries,
+                "issues": entry_issues,
+            }),
+        );
+    }
+
+    match &manifest {
+        Ok(manifest) => println!("{} {}", manifest.name.bold(), manifest.version.dimmed()),
+        Err(_) => println!("{}", args.package_file.bold()),
+    }
+    println!("sha256: {}", checksum);
+    println!(
+        "Size:   {} compressed, {} uncompressed",
+        ByteSize::b(buffer.len() as u64),
+        ByteSize::b(size)
+    );
+    println!();
+
+    match &manifest {
+        Ok(manifest) => {
+            println!("{}", "Manifest (boo.json):".bold());
+            let manifest = serde_json::to_string_pretty(manifest).unwrap_or_else(|e| {
+                print_error(&format!("Failed to print the package definition: {}", e));
+                process::exit(1);
+            });
+            for line in manifest.lines() {
+                println!("  {}", line);
+            }
+        }
+        Err(e) => print_warning(e),
+    }
+    println!();
+
+    for issue in &entry_issues {
+        publish::report_issue(issue);
+    }
+
+    match entry_points.is_empty() {
+        true => print_warning("The package contains neither 'lib.ua' nor 'main.ua'."),
+        false => println!("{} {}", "Entry points:".bold(), entry_points.join(", ")),
+    }
+    println!();
+
+    println!("{}", format!("Entries ({}):", entries.len()).bold());
+    for entry in &entries {
+        let size = match entry.kind {
+            "file" => ByteSize::b(entry.size).to_string(),
+            _ => String::new(),
+        };
+        println!("  {:<9} {:>10}  {}", entry.kind, size.dimmed(), entry.path);
+    }
+}
+
+/// Lists the entries of the archive in the order they are stored in, without
+/// loading their contents.
+fn read_entries(buffer: &[u8]) -> Result<Vec<ArchiveEntry>, String> {
+    let unreadable = |e: std::io::Error| format!("Failed to read the package: {}", e);
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(buffer));
+    let mut entries = Vec::new();
+
+    for entry in archive.entries().map_err(unreadable)? {
+        let entry = entry.map_err(unreadable)?;
+        let kind = match entry.header().entry_type() {
+            EntryType::Regular => "file",
+            EntryType::Directory => "directory",
+            EntryType::Symlink => "symlink",
+            EntryType::Link => "hardlink",
+            EntryType::Char | EntryType::Block | EntryType::Fifo => "device",
+            _ => "other",
+        };
+
+        entries.push(ArchiveEntry {
+            path: String::from_utf8_lossy(&entry.path_bytes()).into_owned(),
+            kind,
+            size: entry.size(),
+        });
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::Path,
+    process,
+};
+
+use crate::{
+    api::BooApiClient,
+    cache::PackageCache,
+    common::{match_files, sha256_hex, BooPackageDefinition, PackageReference},
+    imports::find_boo_imports,
+    lockfile::{LockedPackage, Lockfile, LOCKFILE_NAME},
+    output,
+    resolver::{RegistryIndex, ResolutionConflict, Resolver},
+    print_error, print_success, print_warning, InstallArgs,
+};
+
+pub(crate) fn run_install(args: InstallArgs) {
+    let package = BooPackageDefinition::read_from_file(Path::new("boo.json")).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let references = collect_dependencies(&package).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let lockfile_path = Path::new(LOCKFILE_NAME);
+    let previous_lockfile = match args.update {
+        true => None,
+        false => Lockfile::read_from_file(lockfile_path).unwrap_or_else(|e| {
+            print_error(&e);
+            process::exit(1);
+        }),
+    };
+
+    let cache = PackageCache::new().unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let client = BooApiClient::new();
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+
+    let result = rt.block_on(install_packages(
+        &client,
+        &cache,
+        &package.name,
+        references,
+        previous_lockfile.as_ref(),
+    ));
+
+    let lockfile = match result {
+        Ok(lockfile) => lockfile,
+        Err(InstallError::Message(message)) => {
+            print_error(&message);
+            process::exit(1);
+        }
+        Err(InstallError::Conflict(conflict)) => {
+            print_error("Could not find a set of versions that satisfies all requirements.");
+            print_error(&conflict.summary);
+            for reason in &conflict.reasons {
+                print_error(&format!("- {}", reason));
+            }
+            process::exit(1);
+        }
+    };
+
+    lockfile.write_to_file(lockfile_path).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    output::emit("installed", serde_json::json!({ "packages": lockfile.packages }));
+
+    if lockfile.packages.is_empty() {
+        print_success("No 'boo:' dependencies found, nothing to install.");
+        return;
+    }
+
+    print_success(&format!(
+        "Installed {} package(s) into '{}'.",
+        lockfile.packages.len(),
+        cache.root().display()
+    ));
+}
+
+/// Collects the dependencies declared in `boo.json`, along with the `boo:` imports
+/// from every `.ua` file matched by the package's `include` patterns.
+fn collect_dependencies(package: &BooPackageDefinition) -> Result<BTreeSet<PackageReference>, String> {
+    let mut references = BTreeSet::from_iter(package.dependency_references());
+
+    for pattern in &package.include {
+        let files = match_files(pattern)
+            .map_err(|e| format!("Invalid GLOB pattern '{}': {}", pattern, e))?;
+
+        for file in files.iter().filter(|f| f.extension().is_some_and(|ext| ext == "ua")) {
+            let source = fs::read_to_string(file)
+                .map_err(|e| format!("Failed to read '{}': {}", file.display(), e))?;
+
+            for import in find_boo_imports(&source) {
+                match PackageReference::parse(&import) {
+                    Ok(reference) => {
+                        references.insert(reference);
+                    }
+                    Err(e) => print_warning(&format!(
+                        "Skipping invalid import 'boo:{}' in '{}': {}",
+                        import,
+                        file.display(),
+                        e
+                    )),
+                }
+            }
+        }
+    }
+
+    Ok(references)
+}
+
+enum InstallError {
+    Message(String),
+    Conflict(ResolutionConflict),
+}
+
+impl From<String> for InstallError {
+    fn from(message: String) -> Self {
+        InstallError::Message(message)
+    }
+}
+
+/// Installs the given packages and everything they depend on, returning the new lockfile.
+/// Versions from the previous lockfile are preferred as long as they still satisfy the
+/// requirements, otherwise the newest compatible versions are picked.
+async fn install_packages(
+    client: &BooApiClient,
+    cache: &PackageCache,
+    root_name: &str,
+    references: BTreeSet<PackageReference>,
+    previous_lockfile: Option<&Lockfile>,
+) -> Result<Lockfile, InstallError> {
+    let index = RegistryIndex::fetch(client, &references).await?;
+
+    let preferred_versions = previous_lockfile
+        .map(|lockfile| {
+            lockfile
+                .packages
+                .iter()
+                .map(|(name, locked)| (name.clone(), locked.version.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let resolution = Resolver::new(&index, root_name)
+        .with_preferred_versions(preferred_versions)
+        .resolve(&references)
+        .map_err(InstallError::Conflict)?;
+
+    let mut lockfile = Lockfile::new();
+    for (name, info) in resolution {
+        let locked_checksum = previous_lockfile
+            .and_then(|lockfile| lockfile.packages.get(&name))
+            .filter(|locked| locked.version == info.version)
+            .map(|locked| locked.checksum.clone());
+
+        if locked_checksum.is_none() && info.checksum.is_none() {
+            print_warning(&format!(
+                "The registry did not provide a checksum for {}@{}.",
+                name, info.version
+            ));
+        }
+
+        let expected_checksum = locked_checksum.or(info.checksum);
+        let checksum = fetch_package(
+            client,
+            cache,
+            &name,
+            &info.version,
+            &info.download_url,
+            expected_checksum.as_deref(),
+        )
+        .await?;
+
+        lockfile.packages.insert(
+            name,
+            LockedPackage {
+                version: info.version,
+                download_url: info.download_url,
+                checksum,
+                dependencies: info.dependencies,
+            },
+        );
+    }
+
+    Ok(lockfile)
+}
+
+/// Makes sure the package is present in the cache, downloading it if needed.
+/// Returns the checksum of the package archive.
+async fn fetch_package(
+    client: &BooApiClient,
+    cache: &PackageCache,
+    name: &str,
+    version: &str,
+    download_url: &str,
+    expected_checksum: Option<&str>,
+) -> Result<String, String> {
+    if cache.contains(name, version)
+        && let Some(cached_checksum) = cache.checksum(name, version)
+        && expected_checksum.is_none_or(|expected| expected == cached_checksum)
+    {
+        print_success(&format!("{}@{} is already installed.", name, version));
+        return Ok(cached_checksum);
+    }
+
+    let archive = client
+        .download_package(download_url)
+        .await
+        .map_err(|e| format!("Failed to download {}@{}: {}", name, version, e))?;
+
+    let checksum = sha256_hex(&archive);
+    if let Some(expected) = expected_checksum
+        && expected != checksum
+    {
+        return Err(format!(
+            "Checksum mismatch for {}@{}: expected {}, but the downloaded archive has {}.",
+            name, version, expected, checksum
+        ));
+    }
+
+    let path = cache.store(name, version, &archive, &checksum)?;
+    print_success(&format!(
+        "Installed {}@{} into '{}'.",
+        name,
+        version,
+        path.display()
+    ));
+
+    Ok(checksum)
+}
@@ -0,0 +1,162 @@
+use std::{path::Path, process};
+
+use owo_colors::OwoColorize;
+
+use crate::{
+    LoginArgs,
+    api::BooApiClient,
+    auth::{self, AuthFlowError},
+    common::BooPackageDefinition,
+    credentials::StoredCredentials,
+    output, print_error, print_success, print_warning,
+};
+
+const MAX_TOKEN_EXPIRES_IN_DAYS: u64 = 90;
+
+pub(crate) fn run_login(args: LoginArgs) {
+    if args.days == 0 || args.days > MAX_TOKEN_EXPIRES_IN_DAYS {
+        print_error(&format!(
+            "The token lifetime must be between 1 and {} days.",
+            MAX_TOKEN_EXPIRES_IN_DAYS
+        ));
+        process::exit(1);
+    }
+
+    let mut scopes = args.scope;
+    if scopes.is_empty()
+        && let Ok(package) = BooPackageDefinition::read_from_file(Path::new("boo.json"))
+        && let Some((scope, _)) = package.name.split_once('/')
+    {
+        scopes.push(scope.to_string());
+    }
+
+    if scopes.is_empty() {
+        print_warning("No scope given, the token will not allow publishing packages.");
+    }
+
+    let mut permissions = vec!["user.profile".to_string()];
+    for scope in &scopes {
+        if !scope.starts_with('@') || scope.contains('/') {
+            print_error(&format!("Invalid scope '{}', expected e.g. '@foo'.", scope));
+            process::exit(1);
+        }
+        permissions.push(format!("package.upload-new-version:{}", scope));
+    }
+
+    let mut client = BooApiClient::new();
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let result = rt.block_on(async {
+        let token =
+            auth::request_access_token(&client, permissions, Some(args.days * 24 * 60 * 60))
+                .await?;
+        client.set_access_token(token.clone());
+        let info = client.get_access_token_info().await?;
+        Ok::<_, AuthFlowError>((token, info))
+    });
+
+    let (access_token, info) = result.unwrap_or_else(|e| {
+        match e {
+            AuthFlowError::Denied => print_error("App request was denied, stopping."),
+            AuthFlowError::Api(e) => print_error(&format!("Login failed: {}", e)),
+        }
+        process::exit(1);
+    });
+
+    let credentials = StoredCredentials {
+        api_url: client.base_url.clone(),
+        access_token,
+        username: info.username,
+        permissions: info.permissions,
+        expires_at: info.expires_at,
+    };
+
+    let file_path = credentials.save().unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    if output::is_json() {
+        return output::emit(
+            "logged_in",
+            serde_json::json!({
+                "username": credentials.username,
+                "permissions": credentials.permissions,
+                "expires_at": credentials.expires_at,
+                "credentials_path": file_path,
+            }),
+        );
+    }
+
+    print_success(&format!("Logged in as {}.", credentials.username));
+    println!(
+        "{}",
+        format!("Credentials saved to '{}'.", file_path.display()).dimmed()
+    );
+}
+
+pub(crate) fn run_logout() {
+    let mut client = BooApiClient::new();
+    let credentials = StoredCredentials::load(&client.base_url).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    // Logins to other APIs are kept, their tokens can only be revoked by those servers.
+    let Some(credentials) = credentials else {
+        print_warning("Not logged in.");
+        return;
+    };
+
+    client.set_access_token(credentials.access_token);
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    if let Err(e) = rt.block_on(client.revoke_access_token()) {
+        print_warning(&format!("Could not revoke the token on the server: {}", e));
+    }
+
+    match StoredCredentials::delete(&client.base_url) {
+        Ok(_) => print_success("Logged out."),
+        Err(e) => {
+            print_error(&e);
+            process::exit(1);
+        }
+    }
+}
+
+pub(crate) fn run_whoami() {
+    let mut client = BooApiClient::new();
+    let credentials = StoredCredentials::load(&client.base_url).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let Some(credentials) = credentials else {
+        print_error("Not logged in, run `boo login` first.");
+        process::exit(1);
+    };
+
+    client.set_access_token(credentials.access_token);
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let info = rt
+        .block_on(client.get_access_token_info())
+        .unwrap_or_else(|e| {
+            print_error(&format!(
+                "The stored token is no longer valid, run `boo login` again: {}",
+                e
+            ));
+            process::exit(1);
+        });
+
+    if output::is_json() {
+        return output::emit("whoami", info);
+    }
+
+    println!("Logged in as {}", info.username.bold());
+    println!(
+        "Expires: {}",
+        info.expires_at.as_deref().unwrap_or("never")
+    );
+    println!("Permissions:");
+    for permission in &info.permissions {
+        println!("- {}", permission);
+    }
+}
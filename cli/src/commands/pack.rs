@@ -0,0 +1,199 @@
+use std::{collections::BTreeMap, fs, path::PathBuf, process};
+
+use bytesize::ByteSize;
+use owo_colors::OwoColorize;
+use serde_json::json;
+
+use crate::{PackArgs, common::BooPackageDefinition, output, print_error};
+
+use super::{
+    publish::{self, EXIT_VALIDATION_FAILED},
+    validate::{self, ValidationError, ValidationRules, codes},
+};
+
+/// Share of a size limit from which `--list` warns that a file or the archive is close to it.
+const NEAR_LIMIT_RATIO: f64 = 0.8;
+
+/// A directory of the listed file tree, with the uncompressed size of each file.
+#[derive(Default)]
+struct Directory {
+    directories: BTreeMap<String, Directory>,
+    files: BTreeMap<String, u64>,
+}
+
+pub(crate) fn run_pack(args: PackArgs) {
+    let package_data = publish::get_current_package().unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    if args.show_excluded {
+        publish::report_excluded_files(&package_data.excluded);
+    }
+
+    for issue in package_data.issues.get_sorted_issues() {
+        publish::report_issue(issue);
+    }
+
+    let package_buffer = publish::create_package(&package_data.files).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let rules = ValidationRules::new();
+
+    if args.list {
+        let sizes = file_sizes(&package_data.files).unwrap_or_else(|e| {
+            print_error(&e);
+            process::exit(1);
+        });
+        let compressed_size = package_buffer.len() as u64;
+        print_listing(&package_data.package, &sizes, compressed_size);
+
+        for issue in find_near_limits(&sizes, compressed_size, &rules) {
+            publish::report_issue(&issue);
+        }
+    }
+
+    let validation_results = validate::validate_package(&package_buffer, &rules);
+    for result in &validation_results {
+        publish::report_issue(result);
+    }
+
+    if package_data.issues.has_errors() || validate::has_errors(&validation_results) {
+        process::exit(EXIT_VALIDATION_FAILED);
+    }
+
+    if !args.list {
+        publish::write_package_file(&package_data.package, &package_buffer);
+    }
+}
+
+fn file_sizes(files: &[PathBuf]) -> Result<Vec<(PathBuf, u64)>, String> {
+    files
+        .iter()
+        .map(|file| {
+            fs::metadata(file)
+                .map(|metadata| (file.clone(), metadata.len()))
+                .map_err(|e| format!("Failed to read '{}': {}", file.display(), e))
+        })
+        .collect()
+}
+
+fn print_listing(package: &BooPackageDefinition, sizes: &[(PathBuf, u64)], compressed_size: u64) {
+    let size = sizes.iter().map(|(_, size)| size).sum::<u64>();
+    let ratio = match size {
+        0 => 1.0,
+        _ => compressed_size as f64 / size as f64,
+    };
+
+    if output::is_json() {
+        return output::emit(
+            "package_contents",
+            json!({
+                "name": package.name,
+                "version": package.version,
+                "files": sizes
+                    .iter()
+                    .map(|(path, size)| json!({ "path": path, "size": size }))
+                    .collect::<Vec<_>>(),
+                "size": size,
+                "compressed_size": compressed_size,
+                "ratio": ratio,
+            }),
+        );
+    }
+
+    let mut root = Directory::default();
+    for (path, size) in sizes {
+        let mut directory = &mut root;
+        let mut components = path
+            .iter()
+            .map(|component| component.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let Some(file_name) = components.pop() else {
+            continue;
+        };
+        for component in components {
+            directory = directory.directories.entry(component).or_default();
+        }
+        directory.files.insert(file_name, *size);
+    }
+
+    println!("{} {}", package.name.bold(), package.version.dimmed());
+    print_directory(&root, "");
+    println!();
+    println!(
+        "{} files, {} uncompressed, {} compressed ({:.1}%)",
+        sizes.len(),
+        ByteSize::b(size),
+        ByteSize::b(compressed_size),
+        ratio * 100.0
+    );
+}
+
+fn print_directory(directory: &Directory, prefix: &str) {
+    let entry_count = directory.directories.len() + directory.files.len();
+    let entries = directory
+        .directories
+        .iter()
+        .map(|(name, directory)| (name, Some(directory), 0))
+        .chain(directory.files.iter().map(|(name, size)| (name, None, *size)));
+
+    for (index, (name, subdirectory, size)) in entries.enumerate() {
+        let is_last = index + 1 == entry_count;
+        let branch = if is_last { "└── " } else { "├── " };
+
+        match subdirectory {
+            Some(subdirectory) => {
+                println!("{}{}{}/", prefix, branch, name.bold());
+                let prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+                print_directory(subdirectory, &prefix);
+            }
+            None => println!("{}{}{} {}", prefix, branch, name, ByteSize::b(size).dimmed()),
+        }
+    }
+}
+
+/// Warns about files and an archive that are within the limits of `rules`, but close to them.
+/// Exceeding a limit is reported by the validation itself.
+fn find_near_limits(
+    sizes: &[(PathBuf, u64)],
+    compressed_size: u64,
+    rules: &ValidationRules,
+) -> Vec<ValidationError> {
+    let is_near = |size: u64, limit: u64| {
+        size <= limit && size as f64 >= limit as f64 * NEAR_LIMIT_RATIO
+    };
+
+    let mut warnings = sizes
+        .iter()
+        .filter(|(_, size)| is_near(*size, rules.internal_file_max_size))
+        .map(|(path, size)| {
+            let path = path.to_string_lossy().replace('\\', "/");
+            ValidationError::warning(
+                codes::FILE_NEAR_SIZE_LIMIT,
+                format!(
+                    "The file {} is {}, close to the maximum size of {}.",
+                    path,
+                    ByteSize::b(*size),
+                    ByteSize::b(rules.internal_file_max_size)
+                ),
+            )
+            .with_path(path)
+        })
+        .collect::<Vec<_>>();
+
+    if is_near(compressed_size, rules.compressed_package_max_size) {
+        warnings.push(ValidationError::warning(
+            codes::PACKAGE_NEAR_SIZE_LIMIT,
+            format!(
+                "The compressed package is {}, close to the maximum size of {}.",
+                ByteSize::b(compressed_size),
+                ByteSize::b(rules.compressed_package_max_size)
+            ),
+        ));
+    }
+
+    warnings
+}
@@ -1,6 +1,5 @@
 use std::{
-    cmp,
-    collections::HashSet,
+    collections::{BTreeMap, BTreeSet},
     error::Error,
     fs,
     path::{self, PathBuf},
@@ -10,38 +9,44 @@ use std::{
 };
 
 use owo_colors::OwoColorize;
+use semver::Version;
+use serde_json::json;
 
 use crate::{
     api::{
-        ApiRequestError, AuthRequest, AuthRequestResponse, AuthRequestStatus, BooApiClient,
-        CreatePublishJobRequest, PackagePublishJobStatus, PublishJobResult, ResolvedPackage,
-    }, common::BooPackageDefinition, print_error, print_success, print_warning, PublishArgs
+        ApiRequestError, BooApiClient, CreatePublishJobRequest, PackagePublishJobStatus,
+        PublishJobResult, ResolvedPackage,
+    },
+    auth::{self, AuthFlowError},
+    common::{match_files, normalize_path, BooPackageDefinition},
+    credentials::StoredCredentials,
+    exclude::{ExcludeRule, ExcludeRules},
+    exports::{BreakingChange, PackageApi},
+    output, print_error, print_success, print_warning, PublishArgs,
 };
 
-use flate2::Compression;
-use flate2::write::GzEncoder;
-use glob::glob;
-use tar::Builder;
+use flate2::{Compression, GzBuilder};
+use tar::{Builder, EntryType, Header};
 use tokio::time::sleep;
+use virtual_filesystem::tar_fs::TarFS;
 
-use super::validate;
+use super::validate::{self, codes, Severity, ValidationError};
 
 const POLLING_INTERVAL_SECS: u64 = 1;
-const AUTH_TIMEOUT_SECS: u64 = 300; // 5 minutes
 const PUBLISH_JOB_WAIT_TIMEOUT_SECS: u64 = 600; // 10 minutes
 
-enum PublishingIssueType {
-    Error,
-    Warning,
-}
+/// Environment variable holding a pre-issued access token, e.g. for CI pipelines.
+const TOKEN_ENV: &str = "BOO_TOKEN";
 
-struct PublishingIssue {
-    issue_type: PublishingIssueType,
-    message: String,
-}
+// Exit codes, so scripts can tell apart why publishing failed.
+const EXIT_FAILURE: i32 = 1;
+pub(crate) const EXIT_VALIDATION_FAILED: i32 = 2;
+const EXIT_AUTH_FAILED: i32 = 3;
+const EXIT_NETWORK_FAILED: i32 = 4;
+const EXIT_JOB_FAILED: i32 = 5;
 
-struct PublishingIssues {
-    message: Vec<PublishingIssue>,
+pub(crate) struct PublishingIssues {
+    message: Vec<ValidationError>,
 }
 
 impl PublishingIssues {
@@ -49,42 +54,31 @@ impl PublishingIssues {
         Self { message: vec![] }
     }
 
-    fn add_issue(&mut self, issue_type: PublishingIssueType, message: String) {
-        self.message.push(PublishingIssue {
-            issue_type,
-            message,
-        });
-    }
-
-    fn add_error(&mut self, message: String) {
-        self.add_issue(PublishingIssueType::Error, message);
+    fn add_error(&mut self, code: &str, message: String) {
+        self.message.push(ValidationError::error(code, message));
     }
 
-    fn add_warning(&mut self, message: String) {
-        self.add_issue(PublishingIssueType::Warning, message);
+    fn add_warning(&mut self, code: &str, message: String) {
+        self.message.push(ValidationError::warning(code, message));
     }
 
-    fn has_errors(&self) -> bool {
-        self.message
-            .iter()
-            .any(|issue| matches!(issue.issue_type, PublishingIssueType::Error))
+    pub(crate) fn has_errors(&self) -> bool {
+        validate::has_errors(&self.message)
     }
 
-    fn get_sorted_issues(&self) -> Vec<&PublishingIssue> {
+    pub(crate) fn get_sorted_issues(&self) -> Vec<&ValidationError> {
         let mut sorted_issues = self.message.iter().collect::<Vec<_>>();
-        sorted_issues.sort_by(|a, b| match (&a.issue_type, &b.issue_type) {
-            (PublishingIssueType::Error, PublishingIssueType::Warning) => cmp::Ordering::Greater,
-            (PublishingIssueType::Warning, PublishingIssueType::Error) => cmp::Ordering::Less,
-            _ => cmp::Ordering::Equal,
-        });
+        sorted_issues.sort_by_key(|issue| issue.severity);
         sorted_issues
     }
 }
 
-struct PublishingData {
-    package: BooPackageDefinition,
-    issues: PublishingIssues,
-    files: Vec<PathBuf>,
+pub(crate) struct PublishingData {
+    pub(crate) package: BooPackageDefinition,
+    pub(crate) issues: PublishingIssues,
+    pub(crate) files: Vec<PathBuf>,
+    /// Files matched by `include` but left out, with the rule that excluded them.
+    pub(crate) excluded: Vec<(PathBuf, ExcludeRule)>,
 }
 
 struct VerifiedPackage {
@@ -94,6 +88,7 @@ struct VerifiedPackage {
 
 enum PublishingError {
     AppRequestDenied,
+    AuthError(String),
     PublishJobErrors(Vec<String>),
     ApiError(String),
     NetworkError(String),
@@ -121,151 +116,414 @@ impl From<&ApiRequestError> for PublishingError {
                     .collect::<Vec<_>>()
                     .join(", "),
             ),
-            ApiRequestError::AuthError(message) => PublishingError::ApiError(message.clone()),
+            ApiRequestError::AuthError(message) => PublishingError::AuthError(message.clone()),
         }
     }
 }
 
-pub(crate) fn run_publish(args: PublishArgs) {
-    let package_data = get_current_package().unwrap_or_else(|e| {
-        print_error(&e);
-        process::exit(1);
-    });
+impl PublishingError {
+    fn reason(&self) -> &'static str {
+        match self {
+            PublishingError::AppRequestDenied | PublishingError::AuthError(_) => "auth",
+            PublishingError::NetworkError(_) => "network",
+            PublishingError::PublishJobErrors(_) => "job",
+            PublishingError::ApiError(_) => "api",
+        }
+    }
 
-    for issue in package_data.issues.get_sorted_issues() {
-        match issue.issue_type {
-            PublishingIssueType::Error => print_error(&issue.message.to_string()),
-            PublishingIssueType::Warning => print_warning(&issue.message.to_string()),
+    fn exit_code(&self) -> i32 {
+        match self {
+            PublishingError::AppRequestDenied | PublishingError::AuthError(_) => EXIT_AUTH_FAILED,
+            PublishingError::NetworkError(_) => EXIT_NETWORK_FAILED,
+            PublishingError::PublishJobErrors(_) => EXIT_JOB_FAILED,
+            PublishingError::ApiError(_) => EXIT_FAILURE,
         }
     }
+}
 
-    let package_buffer = create_package(&package_data.files).unwrap_or_else(|e| {
+impl From<AuthFlowError> for PublishingError {
+    fn from(err: AuthFlowError) -> Self {
+        match err {
+            AuthFlowError::Denied => PublishingError::AppRequestDenied,
+            AuthFlowError::Api(err) => PublishingError::from(&err),
+        }
+    }
+}
+
+pub(crate) fn run_publish(args: PublishArgs) {
+    let (package, package_buffer, issues) = match &args.archive {
+        Some(archive) => read_package_archive(archive),
+        None => build_current_package(&args),
+    }
+    .unwrap_or_else(|e| {
         print_error(&e);
         process::exit(1);
     });
 
     let rules = validate::ValidationRules::new();
-    let validation_errors = validate::validate_package(&package_buffer, &rules);
+    let validation_results = validate::validate_package(&package_buffer, &rules);
+
+    for result in &validation_results {
+        report_issue(result);
+    }
 
-    if !validation_errors.is_empty() {
-        for error in validation_errors {
-            print_error(error.message.as_str());
+    if validate::has_errors(&validation_results) {
+        process::exit(EXIT_VALIDATION_FAILED);
+    }
+
+    let mut api_issues = vec![];
+    if !args.offline {
+        match find_breaking_changes(&package, &package_buffer) {
+            Ok(Some((previous_version, changes))) => {
+                for change in changes {
+                    let message = format!(
+                        "{} since version {}, which requires a new major version.",
+                        change, previous_version
+                    );
+                    api_issues.push(match args.allow_breaking {
+                        true => ValidationError::warning(codes::API_BREAKING_CHANGE, message),
+                        false => ValidationError::error(codes::API_BREAKING_CHANGE, message),
+                    });
+                }
+            }
+            Ok(None) => {}
+            Err(e) => print_warning(&format!("Could not check for breaking changes: {}", e)),
         }
-        process::exit(1);
     }
 
-    let has_errors = package_data.issues.has_errors() || !validation_errors.is_empty();
+    for issue in &api_issues {
+        report_issue(issue);
+    }
+
+    let has_errors = issues.has_errors() || validate::has_errors(&api_issues);
 
     if args.check {
+        output::emit("check", json!({ "ready": !has_errors }));
         if !has_errors {
             print_success("No issues found. Package is ready for publishing.");
         }
-        process::exit(has_errors as i32);
+        process::exit(if has_errors { EXIT_VALIDATION_FAILED } else { 0 });
     }
 
     if has_errors {
-        process::exit(1);
+        process::exit(EXIT_VALIDATION_FAILED);
     }
 
     if args.offline {
-        let output_file = format!("{}.tar.gz", package_data.package.package_file_name());
-        fs::write(&output_file, package_buffer).unwrap_or_else(|e| {
-            print_error(&format!(
-                "Failed to write package to file '{}': {}",
-                output_file, e
-            ));
-            process::exit(1);
-        });
-        print_success(&format!("Package created successfully: '{}'", output_file));
+        write_package_file(&package, &package_buffer);
         process::exit(0);
     }
 
-    let status = do_publish(VerifiedPackage {
-        package: package_data.package,
-        buffer: package_buffer,
+    let access_token = provided_access_token(&args).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(EXIT_AUTH_FAILED);
     });
 
-    match status {
+    let status = do_publish(
+        VerifiedPackage {
+            package,
+            buffer: package_buffer,
+        },
+        access_token,
+    );
+
+    let error = match status {
+        Ok(resolved_package) if output::is_json() => {
+            output::emit("published", resolved_package);
+            return;
+        }
         Ok(resolved_package) => {
             print_success("Package published successfully.");
-            println!("");
+            println!();
             println!("The package is now available here:");
             println!("- {}", resolved_package.info_url.underline());
-            println!("");
+            println!();
+            return;
         }
-        Err(PublishingError::AppRequestDenied) => {
+        Err(error) => error,
+    };
+
+    match &error {
+        PublishingError::AppRequestDenied => {
             print_error("App request was denied, stopping.");
         }
-        Err(PublishingError::NetworkError(message)) => {
+        PublishingError::AuthError(message) => {
+            print_error(&format!("Authorization failed: {}", message));
+        }
+        PublishingError::NetworkError(message) => {
             print_error(&format!("Network error: {}", message));
         }
-        Err(PublishingError::ApiError(message)) => {
+        PublishingError::ApiError(message) => {
             print_error(&format!("API error: {}", message));
         }
-        Err(PublishingError::PublishJobErrors(errors)) => {
+        PublishingError::PublishJobErrors(errors) => {
             print_error("Publishing job failed:");
-            for error in &errors {
+            for error in errors {
                 print_error(format!("- {}", error).as_str());
             }
         }
     }
+
+    output::emit(
+        "publish_failed",
+        json!({ "reason": error.reason(), "exit_code": error.exit_code() }),
+    );
+    process::exit(error.exit_code());
+}
+
+/// Collects the files of the package in the current directory and builds its archive.
+fn build_current_package(
+    args: &PublishArgs,
+) -> Result<(BooPackageDefinition, Vec<u8>, PublishingIssues), String> {
+    let package_data = get_current_package()?;
+
+    if args.show_excluded {
+        report_excluded_files(&package_data.excluded);
+    }
+
+    for issue in package_data.issues.get_sorted_issues() {
+        report_issue(issue);
+    }
+
+    let buffer = create_package(&package_data.files)?;
+    Ok((package_data.package, buffer, package_data.issues))
+}
+
+/// Reads a previously built archive, e.g. one written by `--offline`, to upload it
+/// unchanged. The name and version come from the archive's own `boo.json`.
+fn read_package_archive(
+    archive: &str,
+) -> Result<(BooPackageDefinition, Vec<u8>, PublishingIssues), String> {
+    let buffer = fs::read(archive)
+        .map_err(|e| format!("Failed to read package file '{}': {}", archive, e))?;
+
+    // The whole package is loaded into memory below, so its entries are checked first.
+    let entry_issues =
+        validate::validate_archive_entries(&buffer, &validate::ValidationRules::new());
+    if validate::has_errors(&entry_issues) {
+        for issue in &entry_issues {
+            report_issue(issue);
+        }
+        process::exit(EXIT_VALIDATION_FAILED);
+    }
+
+    let mut package = TarFS::new(flate2::read::GzDecoder::new(buffer.as_slice()))
+        .map_err(|e| format!("Failed to read the package '{}': {}", archive, e))?;
+    let definition = validate::try_get_package_definition(&mut package, "boo.json")
+        .map_err(|error| error.message)?;
+
+    Ok((definition, buffer, PublishingIssues::new()))
+}
+
+/// Compares the public API of the package with the closest lower stable published version,
+/// unless the new version is a major bump over it (a minor bump for `0.x` versions).
+///
+/// Returns the version compared to and the breaking changes found, `None` if there is
+/// nothing to compare to.
+fn find_breaking_changes(
+    package: &BooPackageDefinition,
+    buffer: &[u8],
+) -> Result<Option<(Version, Vec<BreakingChange>)>, String> {
+    let version = Version::parse(&package.version)
+        .map_err(|e| format!("Invalid version '{}': {}", package.version, e))?;
+
+    let client = BooApiClient::new();
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+
+    let Some(versions) = rt
+        .block_on(client.find_package_versions(&package.name))
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+
+    let previous = versions
+        .versions
+        .into_iter()
+        .filter_map(|info| Some((Version::parse(&info.version).ok()?, info)))
+        // Pre-releases are not a baseline, like dependency ranges only resolve stable versions.
+        .filter(|(previous_version, _)| {
+            previous_version.pre.is_empty() && *previous_version < version
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+
+    let Some((previous_version, info)) = previous else {
+        return Ok(None);
+    };
+
+    if is_major_bump(&previous_version, &version) {
+        return Ok(None);
+    }
+
+    let previous_buffer = rt
+        .block_on(client.download_package(&info.download_url))
+        .map_err(|e| e.to_string())?;
+
+    let Some(previous_api) = PackageApi::from_archive(&previous_buffer)? else {
+        return Ok(None);
+    };
+    let api = PackageApi::from_archive(buffer)?.unwrap_or_default();
+
+    Ok(Some((previous_version, previous_api.breaking_changes(&api))))
+}
+
+/// Whether a caret range on `previous`, like `^1.2.0` or `^0.3.1`, excludes `version`.
+fn is_major_bump(previous: &Version, version: &Version) -> bool {
+    match (previous.major, previous.minor) {
+        (0, 0) => version.major != 0 || version.minor != 0 || version.patch != previous.patch,
+        (0, _) => version.major != 0 || version.minor != previous.minor,
+        _ => version.major != previous.major,
+    }
+}
+
+pub(crate) fn report_excluded_files(excluded: &[(PathBuf, ExcludeRule)]) {
+    for (file, rule) in excluded {
+        if output::is_json() {
+            output::emit(
+                "excluded",
+                json!({ "path": file, "rule": rule.to_string() }),
+            );
+        } else {
+            println!(
+                "{}",
+                format!("Excluded '{}' by {}", file.display(), rule).dimmed()
+            );
+        }
+    }
+
+    if excluded.is_empty() && !output::is_json() {
+        println!("{}", "No files were excluded.".dimmed());
+    }
+}
+
+/// Writes the package archive to `<name>-<version>.tar.gz` in the current directory.
+pub(crate) fn write_package_file(package: &BooPackageDefinition, buffer: &[u8]) {
+    let output_file = format!("{}.tar.gz", package.package_file_name());
+    fs::write(&output_file, buffer).unwrap_or_else(|e| {
+        print_error(&format!(
+            "Failed to write package to file '{}': {}",
+            output_file, e
+        ));
+        process::exit(1);
+    });
+    output::emit(
+        "package_created",
+        json!({
+            "name": package.name,
+            "version": package.version,
+            "path": output_file,
+        }),
+    );
+    print_success(&format!("Package created successfully: '{}'", output_file));
 }
 
-fn get_current_package() -> Result<PublishingData, String> {
-    let file_path = path::Path::new("boo.json");
-    if !file_path.exists() {
-        return Err("'boo.json' file not found. Please run `boo init` first.".to_string());
+pub(crate) fn report_issue(issue: &ValidationError) {
+    if output::is_json() {
+        return output::emit("issue", issue);
     }
 
-    let file_contents = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read boo.json: {}", e.to_string()))?;
+    match issue.severity {
+        Severity::Error => print_error(&issue.message),
+        Severity::Warning => print_warning(&issue.message),
+    }
+}
 
-    let package_definition: BooPackageDefinition = serde_json::from_str(&file_contents)
-        .map_err(|e| format!("Failed to parse boo.json: {}", e.to_string()))?;
+/// The token given with `--token-file` or `BOO_TOKEN`, if any.
+fn provided_access_token(args: &PublishArgs) -> Result<Option<String>, String> {
+    let token = match &args.token_file {
+        Some(token_file) => fs::read_to_string(token_file)
+            .map_err(|e| format!("Failed to read token file '{}': {}", token_file, e))?,
+        None => match std::env::var(TOKEN_ENV) {
+            Ok(token) => token,
+            Err(_) => return Ok(None),
+        },
+    };
+
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(match &args.token_file {
+            Some(token_file) => format!("Token file '{}' is empty.", token_file),
+            None => format!("{} environment variable is empty.", TOKEN_ENV),
+        });
+    }
+
+    Ok(Some(token.to_string()))
+}
+
+pub(crate) fn get_current_package() -> Result<PublishingData, String> {
+    let package_definition = BooPackageDefinition::read_from_file(path::Path::new("boo.json"))?;
 
     let mut issues = PublishingIssues::new();
 
-    let mut files: HashSet<PathBuf> = HashSet::new();
+    let mut exclude_rules = ExcludeRules::new(&package_definition)?;
+    let mut excluded = BTreeMap::new();
+
+    let mut files: BTreeSet<PathBuf> = BTreeSet::new();
     for pattern in &package_definition.include {
         match match_files(pattern) {
             Ok(matched_files) if !matched_files.is_empty() => {
                 for file in matched_files {
-                    files.insert(file.clone());
+                    // `./lib.ua` and `lib.ua` are the same file in the archive.
+                    let file = normalize_path(&file).unwrap_or(file);
+                    match exclude_rules.excluded_by(&file) {
+                        Some(rule) => {
+                            excluded.insert(file, rule);
+                        }
+                        None => {
+                            files.insert(file);
+                        }
+                    }
                 }
             }
-            Ok(_) => issues.add_warning(format!("No files matched the pattern '{}'", pattern)),
-            Err(e) => issues.add_error(format!("Invalid GLOB pattern '{}': {}", pattern, e)),
+            Ok(_) => issues.add_warning(
+                codes::INCLUDE_NO_MATCH,
+                format!("No files matched the pattern '{}'", pattern),
+            ),
+            Err(e) => issues.add_error(
+                codes::INCLUDE_INVALID_PATTERN,
+                format!("Invalid GLOB pattern '{}': {}", pattern, e),
+            ),
         }
     }
 
-    return Ok(PublishingData {
+    // A rule that can not be parsed excludes nothing, so the files it was meant for would
+    // be published.
+    for error in exclude_rules.ignore_file_errors() {
+        issues.add_error(codes::EXCLUDE_INVALID_IGNORE_FILE, error.clone());
+    }
+
+    Ok(PublishingData {
         package: package_definition,
         issues,
         files: files.into_iter().collect(),
-    });
-}
-
-fn match_files(pattern: &String) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
-    let mut files = vec![];
-
-    for entry in glob(pattern)? {
-        match entry {
-            Ok(path) if path.is_file() => files.push(path),
-            Ok(_) => {}
-            Err(_) => {}
-        }
-    }
-
-    Ok(files)
+        excluded: excluded.into_iter().collect(),
+    })
 }
 
-fn create_package(files: &[PathBuf]) -> Result<Vec<u8>, String> {
-    let encoder = GzEncoder::new(Vec::new(), Compression::default());
+/// Builds the package archive. Entries are sorted and their metadata is fixed, so the
+/// same sources always produce the same bytes and the archive's checksum identifies them.
+pub(crate) fn create_package(files: &[PathBuf]) -> Result<Vec<u8>, String> {
+    let encoder = GzBuilder::new()
+        .mtime(0)
+        .write(Vec::new(), Compression::default());
     let mut tar = Builder::new(encoder);
 
+    let mut files = files.iter().collect::<Vec<_>>();
+    files.sort();
+
     for file in files {
-        let relative_path = file.strip_prefix(".").unwrap_or(file);
-        tar.append_path_with_name(file, relative_path)
+        let contents = fs::read(file)
+            .map_err(|e| format!("Failed to read '{}': {}", file.display(), e))?;
+
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Regular);
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+
+        tar.append_data(&mut header, file, contents.as_slice())
             .map_err(|e| format!("Failed to add file to tar.gz: {}", e))?;
     }
 
@@ -283,39 +541,27 @@ fn create_package(files: &[PathBuf]) -> Result<Vec<u8>, String> {
     Ok(buffer)
 }
 
-fn do_publish(package: VerifiedPackage) -> Result<ResolvedPackage, PublishingError> {
+fn do_publish(
+    package: VerifiedPackage,
+    provided_access_token: Option<String>,
+) -> Result<ResolvedPackage, PublishingError> {
     let mut client = BooApiClient::new();
 
     let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
     rt.block_on(async {
-        let auth_request = AuthRequest {
-            app_name: client.app_name.clone(),
-            requested_permissions: vec![format!(
-                "package.upload-new-version:{}@{}",
-                package.package.name, package.package.version
-            )],
+        let permission = format!(
+            "package.upload-new-version:{}@{}",
+            package.package.name, package.package.version
+        );
+
+        let access_token = match provided_access_token {
+            Some(token) => verify_provided_access_token(token, &permission).await?,
+            None => match stored_access_token(&client, &permission).await {
+                Some(token) => token,
+                None => auth::request_access_token(&client, vec![permission], None).await?,
+            },
         };
-
-        let auth_request_response = client
-            .create_auth_request(auth_request)
-            .await
-            .map_err(|e| PublishingError::from(&e))?;
-
-        println!("");
-        println!("Please approve the application to act on your behalf:");
-        println!("- {}", auth_request_response.request_url.underline());
-        println!("");
-        println!("{}", "Waiting for approval...".dimmed());
-
-        let access_token = run_auth_verification_loop(&client, &auth_request_response).await;
-
-        client
-            .delete_auth_request(&auth_request_response.private_code)
-            .await
-            .map_err(|e| PublishingError::from(&e))?;
-
-        print_success("Authorization approved");
-        client.set_access_token(access_token?);
+        client.set_access_token(access_token);
 
         print_success("Creating publishing job...");
         let publish_job = client
@@ -345,41 +591,67 @@ fn do_publish(package: VerifiedPackage) -> Result<ResolvedPackage, PublishingErr
     })
 }
 
-async fn run_auth_verification_loop(
-    client: &BooApiClient,
-    auth_request_response: &AuthRequestResponse,
+/// Checks that a token given with `--token-file` or `BOO_TOKEN` is valid and allows the upload.
+/// There is nobody to approve a new authorization in that case, so failures are final.
+async fn verify_provided_access_token(
+    token: String,
+    permission: &str,
 ) -> Result<String, PublishingError> {
-    let start_time = std::time::Instant::now();
-
-    loop {
-        if start_time.elapsed().as_secs() > AUTH_TIMEOUT_SECS {
-            return Err(PublishingError::ApiError(
-                "Authorization request timed out".to_string(),
-            ));
-        }
-
-        sleep(Duration::from_secs(POLLING_INTERVAL_SECS)).await;
+    let mut client = BooApiClient::new();
+    client.set_access_token(token.clone());
+
+    let info = client
+        .get_access_token_info()
+        .await
+        .map_err(|e| PublishingError::from(&e))?;
+
+    if !info
+        .permissions
+        .iter()
+        .any(|granted| auth::permission_covers(granted, permission))
+    {
+        return Err(PublishingError::AuthError(format!(
+            "The provided token does not allow publishing {}",
+            permission.trim_start_matches("package.upload-new-version:")
+        )));
+    }
 
-        let request_status = client
-            .get_auth_request_status(&auth_request_response.private_code)
-            .await
-            .map_err(|e| PublishingError::from(&e))?;
+    print_success(&format!("Using provided token of {}", info.username));
+    Ok(token)
+}
 
-        match &request_status.status {
-            AuthRequestStatus::Pending => {
-                continue;
-            }
-            AuthRequestStatus::Approved => match request_status.access_token {
-                Some(token) => return Ok(token),
-                None => {
-                    return Err(PublishingError::ApiError(
-                        "No access token provided in the approval response.".to_string(),
-                    ));
-                }
-            },
-            AuthRequestStatus::Denied => return Err(PublishingError::AppRequestDenied),
+/// Returns the token saved by `boo login` if it is still valid and allows the upload.
+async fn stored_access_token(client: &BooApiClient, permission: &str) -> Option<String> {
+    let credentials = match StoredCredentials::load(&client.base_url) {
+        Ok(credentials) => credentials?,
+        Err(e) => {
+            print_warning(&format!("Ignoring stored credentials: {}", e));
+            return None;
         }
+    };
+
+    let mut stored_client = BooApiClient::new();
+    stored_client.set_access_token(credentials.access_token.clone());
+
+    let Ok(info) = stored_client.get_access_token_info().await else {
+        print_warning("The stored login has expired or was revoked, requesting a new authorization.");
+        return None;
+    };
+
+    if !info
+        .permissions
+        .iter()
+        .any(|granted| auth::permission_covers(granted, permission))
+    {
+        print_warning(&format!(
+            "The stored login does not allow publishing {}, requesting a new authorization.",
+            permission.trim_start_matches("package.upload-new-version:")
+        ));
+        return None;
     }
+
+    print_success(&format!("Using stored login of {}", info.username));
+    Some(credentials.access_token)
 }
 
 async fn run_check_publish_job_status_loop(
@@ -387,12 +659,14 @@ async fn run_check_publish_job_status_loop(
     publishing_id: i64,
 ) -> Result<(), PublishingError> {
     let start_time = std::time::Instant::now();
+    let mut last_status = None;
 
     loop {
         if start_time.elapsed().as_secs() > PUBLISH_JOB_WAIT_TIMEOUT_SECS {
-            return Err(PublishingError::ApiError(
-                "Publishing job timed out".to_string(),
-            ));
+            return Err(PublishingError::PublishJobErrors(vec![format!(
+                "The job did not finish within {} seconds",
+                PUBLISH_JOB_WAIT_TIMEOUT_SECS
+            )]));
         }
 
         sleep(Duration::from_secs(POLLING_INTERVAL_SECS)).await;
@@ -402,6 +676,14 @@ async fn run_check_publish_job_status_loop(
             .await
             .map_err(|e| PublishingError::from(&e))?;
 
+        if last_status.as_ref() != Some(&publish_job.status) {
+            output::emit(
+                "job_status",
+                json!({ "publishing_id": publishing_id, "status": publish_job.status }),
+            );
+            last_status = Some(publish_job.status.clone());
+        }
+
         match publish_job.status {
             PackagePublishJobStatus::Completed => {
                 return Ok(());
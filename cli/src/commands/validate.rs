@@ -1,13 +1,29 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque, hash_map},
+    path::{Path, PathBuf},
+};
+
 use bytesize::ByteSize;
 use serde::{Deserialize, Serialize};
+use tar::EntryType;
 use virtual_filesystem::{FileSystem, tar_fs::TarFS};
 
-use crate::{common::BooPackageDefinition, print_error, print_success, ValidationArgs};
+#[cfg(feature = "compile")]
+mod compile;
+
+use crate::{common::{self, normalize_path, BooPackageDefinition, PackageReference}, exclude::ExcludePatterns, imports::find_imports, output, print_error, print_success, print_warning, ValidationArgs};
+
+/// Rejected archive entries after which the remaining entries are not checked anymore.
+const MAX_ENTRY_ERRORS: usize = 100;
 
 #[derive(Debug, Clone)]
 pub struct ValidationRules {
     pub compressed_package_max_size: u64,
     pub internal_file_max_size: u64,
+    /// Limits the total size of all files after decompression, so archives that
+    /// expand into far more data than they appear to contain are rejected.
+    pub uncompressed_package_max_size: u64,
+    pub max_entry_count: usize,
     pub expected_name: Option<String>,
     pub expected_version: Option<String>,
 }
@@ -17,6 +33,8 @@ impl ValidationRules {
         ValidationRules {
             compressed_package_max_size: ByteSize::mb(5).as_u64(),
             internal_file_max_size: ByteSize::mb(5).as_u64(),
+            uncompressed_package_max_size: ByteSize::mb(25).as_u64(),
+            max_entry_count: 1000,
             expected_name: None,
             expected_version: None,
         }
@@ -33,15 +51,103 @@ impl ValidationRules {
     }
 }
 
+/// Stable identifiers of validation results, safe to match on and to link documentation to.
+pub mod codes {
+    pub const PACKAGE_TOO_LARGE: &str = "package.too-large";
+    pub const PACKAGE_NEAR_SIZE_LIMIT: &str = "package.near-size-limit";
+    pub const PACKAGE_UNCOMPRESSED_TOO_LARGE: &str = "package.uncompressed-too-large";
+    pub const PACKAGE_TOO_MANY_ENTRIES: &str = "package.too-many-entries";
+    pub const PACKAGE_TOO_MANY_ERRORS: &str = "package.too-many-errors";
+    pub const PACKAGE_UNREADABLE: &str = "package.unreadable";
+    pub const PACKAGE_MISSING_ENTRY_POINT: &str = "package.missing-entry-point";
+    pub const FILE_TOO_LARGE: &str = "file.too-large";
+    pub const FILE_NEAR_SIZE_LIMIT: &str = "file.near-size-limit";
+    pub const FILE_NOT_INCLUDED: &str = "file.not-included";
+    pub const FILE_EXCLUDED: &str = "file.excluded";
+    pub const ENTRY_SYMLINK: &str = "entry.symlink";
+    pub const ENTRY_HARDLINK: &str = "entry.hardlink";
+    pub const ENTRY_DEVICE: &str = "entry.device";
+    pub const ENTRY_UNSUPPORTED_TYPE: &str = "entry.unsupported-type";
+    pub const ENTRY_ABSOLUTE_PATH: &str = "entry.absolute-path";
+    pub const ENTRY_PATH_TRAVERSAL: &str = "entry.path-traversal";
+    pub const ENTRY_NON_UTF8_NAME: &str = "entry.non-utf8-name";
+    pub const ENTRY_DUPLICATE_PATH: &str = "entry.duplicate-path";
+    pub const ENTRY_CASE_COLLISION: &str = "entry.case-collision";
+    pub const SOURCE_UNREADABLE: &str = "source.unreadable";
+    pub const IMPORT_INVALID_REFERENCE: &str = "import.invalid-reference";
+    pub const IMPORT_UNDECLARED_DEPENDENCY: &str = "import.undeclared-dependency";
+    pub const IMPORT_OUTSIDE_PACKAGE: &str = "import.outside-package";
+    pub const IMPORT_MISSING_FILE: &str = "import.missing-file";
+    pub const API_BREAKING_CHANGE: &str = "api.breaking-change";
+    #[cfg(feature = "compile")]
+    pub const COMPILE_ERROR: &str = "compile.error";
+    #[cfg(feature = "compile")]
+    pub const COMPILE_SKIPPED: &str = "compile.skipped";
+    #[cfg(feature = "compile")]
+    pub const COMPILE_TIMEOUT: &str = "compile.timeout";
+    pub const DEFINITION_MISSING: &str = "definition.missing";
+    pub const DEFINITION_UNREADABLE: &str = "definition.unreadable";
+    pub const DEFINITION_INVALID: &str = "definition.invalid";
+    pub const DEFINITION_INVALID_NAME: &str = "definition.invalid-name";
+    pub const DEFINITION_INVALID_VERSION: &str = "definition.invalid-version";
+    pub const DEFINITION_INVALID_DEPENDENCY_NAME: &str = "definition.invalid-dependency-name";
+    pub const DEFINITION_INVALID_DEPENDENCY_RANGE: &str = "definition.invalid-dependency-range";
+    pub const DEFINITION_SELF_DEPENDENCY: &str = "definition.self-dependency";
+    pub const DEFINITION_UNEXPECTED_NAME: &str = "definition.unexpected-name";
+    pub const DEFINITION_UNEXPECTED_VERSION: &str = "definition.unexpected-version";
+    pub const INCLUDE_INVALID_PATTERN: &str = "include.invalid-pattern";
+    pub const EXCLUDE_INVALID_PATTERN: &str = "exclude.invalid-pattern";
+    pub const EXCLUDE_INVALID_IGNORE_FILE: &str = "exclude.invalid-ignore-file";
+    pub const INCLUDE_NO_MATCH: &str = "include.no-match";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A single validation result. Only results with [`Severity::Error`] make a package invalid.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationError {
+    pub code: String,
+    pub severity: Severity,
     pub message: String,
+    /// The path inside the archive the result relates to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 impl ValidationError {
-    fn new(message: String) -> Self {
-        ValidationError { message }
+    pub fn error(code: &str, message: String) -> Self {
+        ValidationError {
+            code: code.to_string(),
+            severity: Severity::Error,
+            message,
+            path: None,
+        }
     }
+
+    pub fn warning(code: &str, message: String) -> Self {
+        ValidationError {
+            severity: Severity::Warning,
+            ..Self::error(code, message)
+        }
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+pub fn has_errors(results: &[ValidationError]) -> bool {
+    results.iter().any(ValidationError::is_error)
 }
 
 pub(crate) fn run_validation(args: ValidationArgs) {
@@ -57,26 +163,32 @@ pub(crate) fn run_validation(args: ValidationArgs) {
 
     let results = match std::fs::read(&package_file) {
         Ok(buffer) => validate_package(&buffer, &rules),
-        Err(error) => vec![
-            ValidationError::new(format!(
-                "Failed to read package file '{}': {error}",
-                package_file
-            ))
-        ],
+        Err(error) => vec![ValidationError::error(
+            codes::PACKAGE_UNREADABLE,
+            format!("Failed to read package file '{}': {error}", package_file),
+        )],
     };
     
-    if args.json {
+    // The server parses this array, so it is printed as is instead of as events.
+    if output::is_json() {
         let json_results = serde_json::to_string(&results).expect("Failed to serialize results to JSON");
         println!("{}", json_results);
         return;
     }
 
-    if results.is_empty() {
+    if !has_errors(&results) {
+        for warning in &results {
+            print_warning(&format!("{} [{}]", warning.message, warning.code));
+        }
         print_success("Package validation passed successfully.");
     } else {
         print_error("Package validation failed:");
-        for error in results {
-            print_error(&format!("- {}", error.message));
+        for result in results {
+            let message = format!("- {} [{}]", result.message, result.code);
+            match result.severity {
+                Severity::Error => print_error(&message),
+                Severity::Warning => print_warning(&message),
+            }
         }
     }
 }
@@ -85,120 +197,589 @@ pub(crate) fn validate_package(buffer: &Vec<u8>, rules: &ValidationRules) -> Vec
     let mut errors = Vec::<ValidationError>::new();
 
     if buffer.len() > rules.compressed_package_max_size as usize {
-        errors.push(ValidationError::new(format!(
-            "The compressed package exceeds the maximum size of {} bytes.",
-            ByteSize::b(rules.compressed_package_max_size)
-        )));
+        errors.push(ValidationError::error(
+            codes::PACKAGE_TOO_LARGE,
+            format!(
+                "The compressed package exceeds the maximum size of {}.",
+                ByteSize::b(rules.compressed_package_max_size)
+            ),
+        ));
+    }
+
+    // The archive is scanned before it is loaded into memory below.
+    let entry_errors = validate_archive_entries(buffer, rules);
+    if !entry_errors.is_empty() {
+        errors.extend(entry_errors);
+        return errors;
     }
 
     let decoder = flate2::read::GzDecoder::new(buffer.as_slice());
     let package = TarFS::new(decoder);
     if let Err(error) = package {
-        errors.push(ValidationError::new(format!(
-            "Failed to read the package: {error}"
-        )));
+        errors.push(ValidationError::error(
+            codes::PACKAGE_UNREADABLE,
+            format!("Failed to read the package: {error}"),
+        ));
         return errors;
     }
 
     let mut package = package.unwrap();
     errors.extend(validate_package_files(rules, &package));
 
-    match try_get_package_definition(&mut package, "boo.json") {
+    let definition = match try_get_package_definition(&mut package, "boo.json") {
         Ok(definition) => {
             errors.extend(validate_package_definition(&definition));
             errors.extend(validate_package_definition_by_rules(&definition, rules));
-            // TODO: check if only files defined in 'include' are present
+            errors.extend(validate_included_files(&definition, &package));
+            Some(definition)
         }
-        Err(error) => errors.push(error),
-    }
-
-    let has_lib_file = match &package.exists("lib.ua") {
-        Ok(status) => {
-            if *status {
-                // TODO: Add additional validation for 'lib.ua'
-            }
-            status.clone()
+        Err(error) => {
+            errors.push(error);
+            None
         }
+    };
+
+    let has_lib_file = match package.exists("lib.ua") {
+        Ok(status) => status,
         Err(error) => {
-            errors.push(ValidationError::new(format!(
-                "Failed to check for 'lib.ua': {error}"
-            )));
+            errors.push(
+                ValidationError::error(
+                    codes::PACKAGE_UNREADABLE,
+                    format!("Failed to check for 'lib.ua': {error}"),
+                )
+                .with_path("lib.ua"),
+            );
             false
         }
     };
 
-    let has_main_file = match &package.exists("main.ua") {
-        Ok(status) => {
-            if *status {
-                // TODO: Add additional validation for 'main.ua'
-            }
-            status.clone()
-        }
+    let has_main_file = match package.exists("main.ua") {
+        Ok(status) => status,
         Err(error) => {
-            errors.push(ValidationError::new(format!(
-                "Failed to check for 'main.ua': {error}"
-            )));
+            errors.push(
+                ValidationError::error(
+                    codes::PACKAGE_UNREADABLE,
+                    format!("Failed to check for 'main.ua': {error}"),
+                )
+                .with_path("main.ua"),
+            );
             false
         }
     };
 
     if !has_lib_file && !has_main_file {
-        errors.push(ValidationError::new(
+        errors.push(ValidationError::error(
+            codes::PACKAGE_MISSING_ENTRY_POINT,
             "The package must contain at least one of either 'lib.ua' or 'main.ua'.".to_string(),
         ));
     }
 
+    let entry_files = [("lib.ua", has_lib_file), ("main.ua", has_main_file)]
+        .into_iter()
+        .filter_map(|(file, exists)| exists.then_some(file));
+    errors.extend(validate_sources(&package, entry_files.clone(), definition.as_ref()));
+
+    // Compiling is only worth it once the package itself is known to be sound.
+    #[cfg(feature = "compile")]
+    if !has_errors(&errors) {
+        match list_archive_files(&package) {
+            Ok(files) => errors.extend(compile::compile_sources(&package, &files, entry_files)),
+            Err(error) => errors.push(error),
+        }
+    }
+
     errors
 }
 
-fn validate_package_files(rules: &ValidationRules, fs: &TarFS) -> Vec<ValidationError> {
+/// Follows the imports of the entry files through the package. Local imports must point
+/// to files inside the archive, and `boo:` imports to dependencies declared in `boo.json`.
+fn validate_sources<'a>(
+    package: &TarFS,
+    entry_files: impl IntoIterator<Item = &'a str>,
+    definition: Option<&BooPackageDefinition>,
+) -> Vec<ValidationError> {
     let mut errors = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = entry_files
+        .into_iter()
+        .map(PathBuf::from)
+        .collect::<VecDeque<_>>();
+
+    while let Some(file) = queue.pop_front() {
+        if !visited.insert(file.clone()) {
+            continue;
+        }
 
-    let entries = fs.read_dir("/");
-    if let Err(_) = entries {
-        errors.push(ValidationError::new(
-            "Failed to read the root directory of the archive.".to_string(),
-        ));
-        return errors;
+        let file_path = file.to_string_lossy().replace('\\', "/");
+        let source = match read_archive_file(package, &file_path) {
+            Ok(source) => source,
+            Err(error) => {
+                errors.push(
+                    ValidationError::error(
+                        codes::SOURCE_UNREADABLE,
+                        format!("Failed to read '{}': {}", file_path, error),
+                    )
+                    .with_path(file_path),
+                );
+                continue;
+            }
+        };
+
+        for import in find_imports(&source) {
+            let location = format!("{}:{}", file_path, import.line);
+
+            if let Some(reference) = import.boo_reference() {
+                match PackageReference::parse(reference) {
+                    Err(error) => errors.push(
+                        ValidationError::error(
+                            codes::IMPORT_INVALID_REFERENCE,
+                            format!("Invalid import 'boo:{}': {} ({})", reference, error, location),
+                        )
+                        .with_path(&file_path),
+                    ),
+                    Ok(reference) => {
+                        if let Some(definition) = definition
+                            && !definition.dependencies.contains_key(&reference.name)
+                        {
+                            errors.push(
+                                ValidationError::error(
+                                    codes::IMPORT_UNDECLARED_DEPENDENCY,
+                                    format!(
+                                        "Package {} is imported but not declared in the 'dependencies' of boo.json ({})",
+                                        reference.name, location
+                                    ),
+                                )
+                                .with_path(&file_path),
+                            );
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // Imports like `git:` are resolved by Uiua itself.
+            if import.path.contains(':') {
+                continue;
+            }
+
+            let directory = file.parent().unwrap_or(Path::new(""));
+            let Some(target) = normalize_path(&directory.join(&import.path)) else {
+                errors.push(
+                    ValidationError::error(
+                        codes::IMPORT_OUTSIDE_PACKAGE,
+                        format!(
+                            "Import '{}' points outside of the package ({})",
+                            import.path, location
+                        ),
+                    )
+                    .with_path(&file_path),
+                );
+                continue;
+            };
+
+            match package.exists(&target.to_string_lossy().replace('\\', "/")) {
+                Ok(true) => queue.push_back(target),
+                _ => errors.push(
+                    ValidationError::error(
+                        codes::IMPORT_MISSING_FILE,
+                        format!(
+                            "Imported file '{}' is not part of the package ({})",
+                            import.path, location
+                        ),
+                    )
+                    .with_path(&file_path),
+                ),
+            }
+        }
     }
 
-    let entries = entries.unwrap();
+    errors
+}
+
+fn read_archive_file(package: &TarFS, file_path: &str) -> std::io::Result<String> {
+    let mut file = package.open_file(file_path)?;
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)?;
+    Ok(buf)
+}
+
+/// Streams through the entry headers of the archive without keeping any contents.
+/// Only regular files and directories with plain relative paths are accepted, and
+/// the scan stops as soon as the entry count or total size limits are exceeded, or
+/// after `MAX_ENTRY_ERRORS` rejected entries.
+pub(crate) fn validate_archive_entries(buffer: &[u8], rules: &ValidationRules) -> Vec<ValidationError> {
+    let unreadable = |error: std::io::Error| {
+        vec![ValidationError::error(
+            codes::PACKAGE_UNREADABLE,
+            format!("Failed to read the package: {error}"),
+        )]
+    };
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(buffer));
+    let entries = match archive.entries() {
+        Ok(entries) => entries,
+        Err(error) => return unreadable(error),
+    };
+
+    let mut errors = Vec::new();
+    let mut entry_count = 0;
+    let mut total_size: u64 = 0;
+    let mut seen_paths = HashMap::new();
+    let mut seen_folded_paths = HashMap::new();
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => return unreadable(error),
+        };
+
+        // Every entry counts towards the limits, including the ones rejected below.
+        entry_count += 1;
+        if entry_count > rules.max_entry_count {
+            return vec![ValidationError::error(
+                codes::PACKAGE_TOO_MANY_ENTRIES,
+                format!(
+                    "The package contains more than the maximum of {} entries.",
+                    rules.max_entry_count
+                ),
+            )];
+        }
+
+        total_size = total_size.saturating_add(entry.size());
+        if total_size > rules.uncompressed_package_max_size {
+            return vec![ValidationError::error(
+                codes::PACKAGE_UNCOMPRESSED_TOO_LARGE,
+                format!(
+                    "The uncompressed package exceeds the maximum size of {}.",
+                    ByteSize::b(rules.uncompressed_package_max_size)
+                ),
+            )];
+        }
 
-    for entry in entries.flatten() {
-        if entry.is_directory() {
+        if errors.len() >= MAX_ENTRY_ERRORS {
+            errors.push(ValidationError::error(
+                codes::PACKAGE_TOO_MANY_ERRORS,
+                format!(
+                    "Stopped checking the entries of the package after {} errors.",
+                    MAX_ENTRY_ERRORS
+                ),
+            ));
+            return errors;
+        }
+
+        let path_bytes = entry.path_bytes();
+        let Ok(path) = std::str::from_utf8(&path_bytes) else {
+            errors.push(
+                ValidationError::error(
+                    codes::ENTRY_NON_UTF8_NAME,
+                    "The package contains an entry whose name is not valid UTF-8.".to_string(),
+                )
+                .with_path(String::from_utf8_lossy(&path_bytes)),
+            );
             continue;
+        };
+
+        let entry_type = entry.header().entry_type();
+        if let Err(error) = validate_entry_type(entry_type, path) {
+            errors.push(error);
         }
 
-        let file_size = entry.len();
-        if file_size > rules.internal_file_max_size {
-            errors.push(ValidationError::new(format!(
-                "The file {} exceeds the maximum size of {} bytes.",
-                entry.path.display(),
-                ByteSize::b(rules.internal_file_max_size)
-            )));
+        let Some(path) = validate_entry_path(path, &mut errors) else {
+            continue;
+        };
+
+        if path.is_empty() {
+            continue;
+        }
+
+        // Paths map to whether they are directories. Parent directories do not need an
+        // entry of their own, but can not be files.
+        let is_dir = entry_type.is_dir();
+        let file_parent = path
+            .match_indices('/')
+            .map(|(index, _)| &path[..index])
+            .find(|parent| seen_paths.get(*parent) == Some(&false));
+        if let Some(parent) = file_parent {
+            errors.push(file_directory_clash(parent));
+            continue;
+        }
+        for (index, _) in path.match_indices('/') {
+            seen_paths.entry(path[..index].to_string()).or_insert(true);
+        }
+
+        match seen_paths.get(&path) {
+            // Directories may be listed more than once, e.g. by tools adding parents.
+            Some(true) if is_dir => continue,
+            Some(&was_dir) => {
+                errors.push(match was_dir == is_dir {
+                    true => ValidationError::error(
+                        codes::ENTRY_DUPLICATE_PATH,
+                        format!("The path '{}' appears more than once in the package.", path),
+                    )
+                    .with_path(path),
+                    false => file_directory_clash(&path),
+                });
+                continue;
+            }
+            None => {
+                seen_paths.insert(path.clone(), is_dir);
+            }
+        }
+
+        match seen_folded_paths.entry(path.to_lowercase()) {
+            hash_map::Entry::Occupied(existing) => errors.push(
+                ValidationError::error(
+                    codes::ENTRY_CASE_COLLISION,
+                    format!(
+                        "The paths '{}' and '{}' only differ in case.",
+                        existing.get(),
+                        path
+                    ),
+                )
+                .with_path(path),
+            ),
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert(path);
+            }
+        }
+    }
+
+    errors
+}
+
+fn file_directory_clash(path: &str) -> ValidationError {
+    ValidationError::error(
+        codes::ENTRY_DUPLICATE_PATH,
+        format!("The path '{}' is used for both a file and a directory.", path),
+    )
+    .with_path(path)
+}
+
+fn validate_entry_type(entry_type: EntryType, path: &str) -> Result<(), ValidationError> {
+    let (code, kind) = match entry_type {
+        EntryType::Regular | EntryType::Directory => return Ok(()),
+        EntryType::Symlink => (codes::ENTRY_SYMLINK, "a symbolic link"),
+        EntryType::Link => (codes::ENTRY_HARDLINK, "a hard link"),
+        EntryType::Char | EntryType::Block | EntryType::Fifo => {
+            (codes::ENTRY_DEVICE, "a device or special file")
+        }
+        _ => (codes::ENTRY_UNSUPPORTED_TYPE, "an unsupported entry type"),
+    };
+
+    Err(ValidationError::error(
+        code,
+        format!(
+            "The entry '{}' is {}, only regular files and directories are allowed.",
+            path, kind
+        ),
+    )
+    .with_path(path))
+}
+
+/// Normalizes an entry path by dropping `.` components and trailing slashes,
+/// reporting absolute paths and `..` components instead.
+fn validate_entry_path(path: &str, errors: &mut Vec<ValidationError>) -> Option<String> {
+    let has_drive_prefix = path.as_bytes().get(1) == Some(&b':');
+    if path.starts_with(['/', '\\']) || has_drive_prefix {
+        errors.push(
+            ValidationError::error(
+                codes::ENTRY_ABSOLUTE_PATH,
+                format!("The entry '{}' has an absolute path.", path),
+            )
+            .with_path(path),
+        );
+        return None;
+    }
+
+    let components = path
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+
+    if components.contains(&"..") {
+        errors.push(
+            ValidationError::error(
+                codes::ENTRY_PATH_TRAVERSAL,
+                format!("The entry '{}' points outside of the package.", path),
+            )
+            .with_path(path),
+        );
+        return None;
+    }
+
+    Some(components.join("/"))
+}
+
+fn validate_package_files(rules: &ValidationRules, fs: &TarFS) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let files = match list_archive_files(fs) {
+        Ok(files) => files,
+        Err(error) => {
+            errors.push(error);
+            return errors;
+        }
+    };
+
+    for file in files {
+        if file.size > rules.internal_file_max_size {
+            errors.push(
+                ValidationError::error(
+                    codes::FILE_TOO_LARGE,
+                    format!(
+                        "The file {} exceeds the maximum size of {}.",
+                        file.path,
+                        ByteSize::b(rules.internal_file_max_size)
+                    ),
+                )
+                .with_path(file.path),
+            );
         }
     }
 
     errors
 }
 
+/// Checks that the archive only contains files matched by the `include` patterns of its `boo.json`.
+fn validate_included_files(definition: &BooPackageDefinition, fs: &TarFS) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let mut patterns = Vec::new();
+    for pattern in &definition.include {
+        match common::include_pattern(pattern) {
+            Ok(compiled) => patterns.push(compiled),
+            Err(error) => errors.push(
+                ValidationError::error(
+                    codes::INCLUDE_INVALID_PATTERN,
+                    format!("Invalid GLOB pattern '{}': {}", pattern, error),
+                )
+                .with_path("boo.json"),
+            ),
+        }
+    }
+
+    let exclude_patterns = match ExcludePatterns::new(&definition.exclude) {
+        Ok(patterns) => Some(patterns),
+        Err(messages) => {
+            errors.extend(messages.into_iter().map(|message| {
+                ValidationError::error(codes::EXCLUDE_INVALID_PATTERN, message)
+                    .with_path("boo.json")
+            }));
+            None
+        }
+    };
+
+    let files = match list_archive_files(fs) {
+        Ok(files) => files,
+        Err(error) => {
+            errors.push(error);
+            return errors;
+        }
+    };
+
+    for file in files {
+        if let Some(pattern) = exclude_patterns
+            .as_ref()
+            .and_then(|patterns| patterns.matching(&file.path))
+        {
+            errors.push(
+                ValidationError::error(
+                    codes::FILE_EXCLUDED,
+                    format!(
+                        "The file '{}' is matched by the exclude pattern '{}' in boo.json.",
+                        file.path, pattern
+                    ),
+                )
+                .with_path(file.path),
+            );
+        } else if !patterns
+            .iter()
+            .any(|pattern| common::include_pattern_matches(pattern, &file.path))
+        {
+            errors.push(
+                ValidationError::error(
+                    codes::FILE_NOT_INCLUDED,
+                    format!(
+                        "The file '{}' is not matched by any 'include' pattern in boo.json.",
+                        file.path
+                    ),
+                )
+                .with_path(file.path),
+            );
+        }
+    }
+
+    errors
+}
+
+struct ArchiveFile {
+    /// Path relative to the root of the archive.
+    path: String,
+    size: u64,
+}
+
+/// Lists all files in the archive, walking through every directory.
+fn list_archive_files(fs: &TarFS) -> Result<Vec<ArchiveFile>, ValidationError> {
+    let mut files = Vec::new();
+    let mut directories = vec![String::new()];
+
+    while let Some(directory) = directories.pop() {
+        let entries = fs.read_dir(&format!("/{}", directory)).map_err(|error| {
+            ValidationError::error(
+                codes::PACKAGE_UNREADABLE,
+                format!("Failed to read the directory '/{}' of the archive: {}", directory, error),
+            )
+        })?;
+
+        for entry in entries.flatten() {
+            let path = match directory.is_empty() {
+                true => entry.path.display().to_string(),
+                false => format!("{}/{}", directory, entry.path.display()),
+            };
+
+            match entry.is_directory() {
+                true => directories.push(path),
+                false => files.push(ArchiveFile {
+                    path,
+                    size: entry.len(),
+                }),
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
 // Extracted function to handle opening, reading, and parsing boo.json
-fn try_get_package_definition(
+pub(crate) fn try_get_package_definition(
     package: &mut TarFS,
     file_path: &str,
 ) -> Result<BooPackageDefinition, ValidationError> {
     let mut file = package.open_file(file_path).map_err(|error| {
-        ValidationError::new(format!(
-            "Could not open '{file_path}' file in the archive: {error}"
-        ))
+        ValidationError::error(
+            codes::DEFINITION_MISSING,
+            format!("Could not open '{file_path}' file in the archive: {error}"),
+        )
+        .with_path(file_path)
     })?;
 
     let mut buf = String::new();
-    file.read_to_string(&mut buf)
-        .map_err(|error| ValidationError::new(format!("Failed to read '{file_path}': {error}")))?;
+    file.read_to_string(&mut buf).map_err(|error| {
+        ValidationError::error(
+            codes::DEFINITION_UNREADABLE,
+            format!("Failed to read '{file_path}': {error}"),
+        )
+        .with_path(file_path)
+    })?;
 
-    serde_json::from_str::<BooPackageDefinition>(&buf)
-        .map_err(|error| ValidationError::new(format!("Invalid '{file_path}' format: {error}")))
+    serde_json::from_str::<BooPackageDefinition>(&buf).map_err(|error| {
+        ValidationError::error(
+            codes::DEFINITION_INVALID,
+            format!("Invalid '{file_path}' format: {error}"),
+        )
+        .with_path(file_path)
+    })
 }
 
 pub fn validate_package_definition(definition: &BooPackageDefinition) -> Vec<ValidationError> {
@@ -209,10 +790,45 @@ pub fn validate_package_definition(definition: &BooPackageDefinition) -> Vec<Val
     }
 
     if let Err(error) = semver::Version::parse(&definition.version) {
-        errors.push(ValidationError::new(format!(
-            "Invalid package version '{}': {}",
-            definition.version, error
-        )));
+        errors.push(ValidationError::error(
+            codes::DEFINITION_INVALID_VERSION,
+            format!("Invalid package version '{}': {}", definition.version, error),
+        ));
+    }
+
+    for (name, version_req) in &definition.dependencies {
+        if let Err(error) = validate_package_name(name) {
+            errors.push(
+                ValidationError::error(
+                    codes::DEFINITION_INVALID_DEPENDENCY_NAME,
+                    format!("Invalid dependency name '{}': {}", name, error.message),
+                )
+                .with_path("boo.json"),
+            );
+        }
+
+        if *name == definition.name {
+            errors.push(
+                ValidationError::error(
+                    codes::DEFINITION_SELF_DEPENDENCY,
+                    format!("Package '{}' cannot depend on itself", name),
+                )
+                .with_path("boo.json"),
+            );
+        }
+
+        if let Err(error) = common::VersionRange::parse(version_req) {
+            errors.push(
+                ValidationError::error(
+                    codes::DEFINITION_INVALID_DEPENDENCY_RANGE,
+                    format!(
+                        "Invalid version range '{}' for dependency '{}': {}",
+                        version_req, name, error
+                    ),
+                )
+                .with_path("boo.json"),
+            );
+        }
     }
 
     errors
@@ -221,22 +837,28 @@ pub fn validate_package_definition(definition: &BooPackageDefinition) -> Vec<Val
 fn validate_package_definition_by_rules(definition: &BooPackageDefinition, rules: &ValidationRules) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
-    if let Some(expected_name) = &rules.expected_name {
-        if definition.name != *expected_name {
-            errors.push(ValidationError::new(format!(
+    if let Some(expected_name) = &rules.expected_name
+        && definition.name != *expected_name
+    {
+        errors.push(ValidationError::error(
+            codes::DEFINITION_UNEXPECTED_NAME,
+            format!(
                 "Expected package name '{}' but found '{}'",
                 expected_name, definition.name
-            )));
-        }
+            ),
+        ));
     }
 
-    if let Some(expected_version) = &rules.expected_version {
-        if definition.version != *expected_version {
-            errors.push(ValidationError::new(format!(
+    if let Some(expected_version) = &rules.expected_version
+        && definition.version != *expected_version
+    {
+        errors.push(ValidationError::error(
+            codes::DEFINITION_UNEXPECTED_VERSION,
+            format!(
                 "Expected package version '{}' but found '{}'",
                 expected_version, definition.version
-            )));
-        }
+            ),
+        ));
     }
 
     errors
@@ -244,11 +866,11 @@ fn validate_package_definition_by_rules(definition: &BooPackageDefinition, rules
 
 pub fn validate_package_name(name: &String) -> Result<String, ValidationError> {
     if name.is_empty() {
-        return Err(ValidationError::new("package name cannot be empty".to_string()));
+        return Err(invalid_name("package name cannot be empty".to_string()));
     }
 
     if !name.contains('/') {
-        return Err(ValidationError::new(
+        return Err(invalid_name(
             "package name must be in the format 'scope-name/package-name'".to_string(),
         ));
     }
@@ -256,12 +878,12 @@ pub fn validate_package_name(name: &String) -> Result<String, ValidationError> {
     let stripped_name = name
         .strip_prefix("@")
         .ok_or_else(|| {
-            ValidationError::new(format!("package name '{}' must start with '@'", name))
+            invalid_name(format!("package name '{}' must start with '@'", name))
         })?;
     
     let parts: Vec<&str> = stripped_name.split('/').collect();
     if parts.len() != 2 {
-        return Err(ValidationError::new(
+        return Err(invalid_name(
             "package name must contain exactly one '/'".to_string(),
         ));
     }
@@ -275,25 +897,25 @@ pub fn validate_package_name(name: &String) -> Result<String, ValidationError> {
 
 fn validate_package_name_piece(name: &str, piece: &str) -> Result<(), ValidationError> {
     if name.is_empty() {
-        return Err(ValidationError::new(format!("{} cannot be empty", piece)));
+        return Err(invalid_name(format!("{} cannot be empty", piece)));
     }
 
     if name.chars().any(|c| !c.is_ascii_alphanumeric() && c != '-') {
-        return Err(ValidationError::new(format!(
+        return Err(invalid_name(format!(
             "{} can only contain alphanumeric characters and dashes",
             piece
         )));
     }
 
     if name.starts_with('-') || name.ends_with('-') {
-        return Err(ValidationError::new(format!(
+        return Err(invalid_name(format!(
             "{} cannot start or end with a dash",
             piece
         )));
     }
 
     if name.contains("--") {
-        return Err(ValidationError::new(format!(
+        return Err(invalid_name(format!(
             "{} cannot contain consecutive dashes",
             piece
         )));
@@ -304,7 +926,7 @@ fn validate_package_name_piece(name: &str, piece: &str) -> Result<(), Validation
     let max_length = 32;
 
     if length < min_length || length > max_length {
-        return Err(ValidationError::new(format!(
+        return Err(invalid_name(format!(
             "{} must be between {} and {} characters long",
             piece, min_length, max_length
         )));
@@ -312,3 +934,7 @@ fn validate_package_name_piece(name: &str, piece: &str) -> Result<(), Validation
 
     Ok(())
 }
+
+fn invalid_name(message: String) -> ValidationError {
+    ValidationError::error(codes::DEFINITION_INVALID_NAME, message)
+}
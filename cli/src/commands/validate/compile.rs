@@ -0,0 +1,240 @@
+//! Compiles the sources of a package with the Uiua compiler, without touching the
+//! file system or the network. Only files of the archive can be imported, `comptime`
+//! code is not run and each compilation is bounded by a timeout.
+
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use uiua::{CodeSpan, Compiler, InputSrc, PreEvalMode, Span, SysBackend, UiuaError, UiuaErrorKind};
+use virtual_filesystem::tar_fs::TarFS;
+
+use super::{ArchiveFile, ValidationError, codes, read_archive_file};
+use crate::{common::normalize_path, imports::find_imports};
+
+/// How long compiling a single entry file may take.
+const COMPILE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A backend that serves the `.ua` files of the archive and refuses any other IO.
+struct ArchiveSys {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl ArchiveSys {
+    fn lookup(&self, path: &Path) -> Option<&Vec<u8>> {
+        let path = normalize_path(path)?;
+        self.files.get(&path.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+impl SysBackend for ArchiveSys {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        self.lookup(Path::new(path)).is_some()
+    }
+
+    fn is_file(&self, path: &str) -> Result<bool, String> {
+        Ok(self.file_exists(path))
+    }
+
+    fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
+        self.lookup(path)
+            .cloned()
+            .ok_or_else(|| format!("'{}' is not part of the package", path.display()))
+    }
+}
+
+/// Compiles the entry files of the package and reports every compile error with the
+/// file and line it occurred in.
+///
+/// Entry files that depend on other packages or on `git:` imports are skipped, as
+/// those sources are not part of the archive.
+pub(super) fn compile_sources<'a>(
+    package: &TarFS,
+    files: &[ArchiveFile],
+    entry_files: impl IntoIterator<Item = &'a str>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut sources = HashMap::new();
+
+    for file in files.iter().filter(|file| file.path.ends_with(".ua")) {
+        match read_archive_file(package, &file.path) {
+            Ok(source) => {
+                sources.insert(file.path.clone(), source);
+            }
+            Err(error) => errors.push(
+                ValidationError::error(
+                    codes::SOURCE_UNREADABLE,
+                    format!("Failed to read '{}': {}", file.path, error),
+                )
+                .with_path(&file.path),
+            ),
+        }
+    }
+
+    for entry_file in entry_files {
+        let Some(source) = sources.get(entry_file) else {
+            continue;
+        };
+
+        if let Some(import) = find_external_import(&sources, entry_file) {
+            errors.push(
+                ValidationError::warning(
+                    codes::COMPILE_SKIPPED,
+                    format!(
+                        "Skipped compiling '{}', it imports '{}' which is not part of the package.",
+                        entry_file, import
+                    ),
+                )
+                .with_path(entry_file),
+            );
+            continue;
+        }
+
+        let backend = ArchiveSys {
+            files: sources
+                .iter()
+                .map(|(path, source)| (path.clone(), source.clone().into_bytes()))
+                .collect(),
+        };
+
+        errors.extend(compile_with_timeout(backend, source.clone(), entry_file));
+    }
+
+    errors
+}
+
+/// Compiles an entry file on its own thread, giving up after `COMPILE_TIMEOUT`.
+///
+/// `comptime` is disabled, but code macros still run at compile time and Uiua has no way
+/// to bound them. A package that never finishes compiling is reported instead of hanging
+/// the validation; its thread is left behind and ends with the process.
+fn compile_with_timeout(
+    backend: ArchiveSys,
+    source: String,
+    entry_file: &str,
+) -> Vec<ValidationError> {
+    let (sender, receiver) = mpsc::channel();
+    let thread_entry_file = entry_file.to_string();
+
+    thread::spawn(move || {
+        let mut compiler = Compiler::with_backend(backend);
+        compiler
+            .comptime(false)
+            .pre_eval_mode(PreEvalMode::Lazy)
+            .print_diagnostics(false);
+
+        let errors = match compiler.load_str_src(&source, Path::new(&thread_entry_file)) {
+            Ok(_) => Vec::new(),
+            Err(error) => error
+                .into_multi()
+                .into_iter()
+                .flat_map(|error| compile_errors(error, &thread_entry_file))
+                .collect(),
+        };
+        let _ = sender.send(errors);
+    });
+
+    match receiver.recv_timeout(COMPILE_TIMEOUT) {
+        Ok(errors) => errors,
+        Err(RecvTimeoutError::Timeout) => vec![
+            ValidationError::error(
+                codes::COMPILE_TIMEOUT,
+                format!(
+                    "Compiling '{}' did not finish within {} seconds.",
+                    entry_file,
+                    COMPILE_TIMEOUT.as_secs()
+                ),
+            )
+            .with_path(entry_file),
+        ],
+        // The compiler panicked.
+        Err(RecvTimeoutError::Disconnected) => vec![
+            ValidationError::error(
+                codes::COMPILE_ERROR,
+                format!("The compiler crashed while compiling '{}'.", entry_file),
+            )
+            .with_path(entry_file),
+        ],
+    }
+}
+
+/// Finds the first import reachable from the entry file that can not be served from
+/// the archive, like `boo:` dependencies or `git:` repositories.
+fn find_external_import(sources: &HashMap<String, String>, entry_file: &str) -> Option<String> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([PathBuf::from(entry_file)]);
+
+    while let Some(file) = queue.pop_front() {
+        if !visited.insert(file.clone()) {
+            continue;
+        }
+
+        let Some(source) = sources.get(&*file.to_string_lossy().replace('\\', "/")) else {
+            continue;
+        };
+
+        for import in find_imports(source) {
+            if import.path.contains(':') {
+                return Some(import.path);
+            }
+
+            let directory = file.parent().unwrap_or(Path::new(""));
+            if let Some(target) = normalize_path(&directory.join(&import.path)) {
+                queue.push_back(target);
+            }
+        }
+    }
+
+    None
+}
+
+fn compile_errors(error: UiuaError, entry_file: &str) -> Vec<ValidationError> {
+    let messages = match *error.kind {
+        UiuaErrorKind::Parse(errors, _) => errors
+            .into_iter()
+            .map(|error| (error.value.to_string(), Span::Code(error.span)))
+            .collect(),
+        UiuaErrorKind::Run { message, .. } => vec![(message.value, message.span)],
+        UiuaErrorKind::Throw(value, span, _) => vec![(value.to_string(), span)],
+        kind => vec![(UiuaError::from(kind).to_string(), Span::Builtin)],
+    };
+
+    messages
+        .into_iter()
+        .map(|(message, span)| {
+            let (path, line) = match span {
+                Span::Code(CodeSpan {
+                    src: InputSrc::File(path),
+                    start,
+                    ..
+                }) => (path.to_string_lossy().replace('\\', "/"), Some(start.line)),
+                Span::Code(span) => (entry_file.to_string(), Some(span.start.line)),
+                Span::Builtin => (entry_file.to_string(), None),
+            };
+
+            let location = match line {
+                Some(line) => format!("{}:{}", path, line),
+                None => path.clone(),
+            };
+
+            ValidationError::error(
+                codes::COMPILE_ERROR,
+                format!("{} ({})", message.trim_end(), location),
+            )
+            .with_path(path)
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+use std::{fs, path::Path, process};
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+use crate::{
+    VersionArgs, VersionBump, common::BooPackageDefinition, json_edit, output, print_error,
+    print_success,
+};
+
+/// Identifier of new pre-releases when neither `--preid` nor an existing pre-release gives one.
+const DEFAULT_PRERELEASE_ID: &str = "beta";
+
+pub(crate) fn run_version(args: VersionArgs) {
+    let definition_path = Path::new("boo.json");
+    let package = BooPackageDefinition::read_from_file(definition_path).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let current = Version::parse(&package.version).unwrap_or_else(|e| {
+        print_error(&format!("Invalid version '{}' in boo.json: {}", package.version, e));
+        process::exit(1);
+    });
+
+    let version = bump_version(&current, args.bump, args.preid.as_deref()).unwrap_or_else(|e| {
+        print_error(&e);
+        process::exit(1);
+    });
+
+    let contents = fs::read_to_string(definition_path).unwrap_or_else(|e| {
+        print_error(&format!("Failed to read boo.json: {}", e));
+        process::exit(1);
+    });
+
+    let updated = json_edit::set_string(&contents, "version", &version.to_string())
+        .unwrap_or_else(|e| {
+            print_error(&format!("Failed to update boo.json: {}", e));
+            process::exit(1);
+        });
+
+    if let Err(e) = fs::write(definition_path, updated) {
+        print_error(&format!("Failed to write to boo.json: {}", e));
+        process::exit(1);
+    }
+
+    let tag = args.git_tag.then(|| format!("v{}", version));
+    if let Some(tag) = &tag
+        && let Err(e) = commit_and_tag(definition_path, tag)
+    {
+        print_error(&e);
+        process::exit(1);
+    }
+
+    output::emit(
+        "version_bumped",
+        serde_json::json!({
+            "previous": current.to_string(),
+            "version": version.to_string(),
+            "tag": tag,
+        }),
+    );
+
+    match &tag {
+        Some(tag) => print_success(&format!(
+            "Bumped version from {} to {} and tagged it as {}.",
+            current, version, tag
+        )),
+        None => print_success(&format!("Bumped version from {} to {}.", current, version)),
+    }
+}
+
+/// Bumps a version the same way `npm version` does: a pre-release of the bumped
+/// version is released by dropping its pre-release part, e.g. a patch bump of
+/// `1.3.0-beta.2` gives `1.3.0`. Pre-releases count up their last number.
+fn bump_version(current: &Version, bump: VersionBump, preid: Option<&str>) -> Result<Version, String> {
+    let mut version = current.clone();
+    let is_prerelease = !current.pre.is_empty();
+    version.build = BuildMetadata::EMPTY;
+    version.pre = Prerelease::EMPTY;
+
+    match bump {
+        VersionBump::Major => {
+            if !is_prerelease || current.minor != 0 || current.patch != 0 {
+                version.major += 1;
+                version.minor = 0;
+                version.patch = 0;
+            }
+        }
+        VersionBump::Minor => {
+            if !is_prerelease || current.patch != 0 {
+                version.minor += 1;
+                version.patch = 0;
+            }
+        }
+        VersionBump::Patch => {
+            if !is_prerelease {
+                version.patch += 1;
+            }
+        }
+        VersionBump::Prerelease => {
+            let (current_id, current_number) = split_prerelease(&current.pre);
+            let id = preid.unwrap_or(match is_prerelease {
+                true => current_id,
+                false => DEFAULT_PRERELEASE_ID,
+            });
+
+            let number = match current_number {
+                Some(number) if is_prerelease && id == current_id => number + 1,
+                _ => 1,
+            };
+
+            if !is_prerelease {
+                version.patch += 1;
+            }
+
+            version.pre = Prerelease::new(&format!("{}.{}", id, number))
+                .map_err(|e| format!("Invalid pre-release identifier '{}': {}", id, e))?;
+        }
+    }
+
+    Ok(version)
+}
+
+/// Splits a pre-release like `beta.2` into its identifier and trailing number.
+fn split_prerelease(pre: &Prerelease) -> (&str, Option<u64>) {
+    match pre.as_str().rsplit_once('.') {
+        Some((id, number)) => match number.parse() {
+            Ok(number) => (id, Some(number)),
+            Err(_) => (pre.as_str(), None),
+        },
+        None => (pre.as_str(), None),
+    }
+}
+
+fn commit_and_tag(definition_path: &Path, tag: &str) -> Result<(), String> {
+    let run_git = |args: &[&str]| {
+        let status = process::Command::new("git")
+            .args(args)
+            .status()
+            .map_err(|e| format!("Failed to run git: {}", e))?;
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("`git {}` failed.", args.join(" "))),
+        }
+    };
+
+    let definition_path = definition_path.to_string_lossy();
+    run_git(&["commit", "--quiet", "-m", tag, "--", &definition_path])?;
+    run_git(&["tag", tag])
+}
@@ -1,22 +1,364 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Component, Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern, PatternError, glob};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BooPackageDefinition {
     pub name: String,
     pub version: String,
     pub include: Vec<String>,
+    /// Patterns of files to leave out even though they match `include`, matched like the
+    /// lines of a `.booignore` file: `*.bak` at any depth, `target/` with everything below it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Whether files ignored by `.gitignore` files are left out as well.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub gitignore: bool,
+    /// Package names mapped to the range of versions that satisfy them, see [`VersionRange`].
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
 }
 
 impl BooPackageDefinition {
+    pub fn read_from_file(file_path: &Path) -> Result<Self, String> {
+        if !file_path.exists() {
+            return Err("'boo.json' file not found. Please run `boo init` first.".to_string());
+        }
+
+        let file_contents = fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to read boo.json: {}", e))?;
+
+        serde_json::from_str(&file_contents)
+            .map_err(|e| format!("Failed to parse boo.json: {}", e))
+    }
+
     pub fn package_file_name(&self) -> String {
         let clean_name = self.name
             .replace("/", "-")
             .replace("@", "");
-        
+
         format!("{}-{}", clean_name, self.version)
     }
 
     pub fn version_reference(&self) -> String {
         format!("{}@{}", self.name, self.version)
     }
+
+    pub fn dependency_references(&self) -> Vec<PackageReference> {
+        self.dependencies
+            .iter()
+            .map(|(name, version)| PackageReference {
+                name: name.clone(),
+                version: Some(version.clone()),
+            })
+            .collect()
+    }
+}
+
+/// A reference to a package as written in a `boo:` import, e.g. `@scope/name@1.2.3`.
+/// The version is optional, in which case the registry picks the latest stable version.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PackageReference {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl PackageReference {
+    pub fn parse(reference: &str) -> Result<Self, String> {
+        let stripped = reference
+            .strip_prefix('@')
+            .ok_or_else(|| format!("package reference '{}' must start with '@'", reference))?;
+
+        let (name, version) = match stripped.split_once('@') {
+            Some((name, version)) if !version.is_empty() => (name, Some(version.to_string())),
+            Some(_) => {
+                return Err(format!(
+                    "package reference '{}' has an empty version",
+                    reference
+                ));
+            }
+            None => (stripped, None),
+        };
+
+        Ok(PackageReference {
+            name: format!("@{}", name),
+            version,
+        })
+    }
+
+    /// Checks whether a concrete version satisfies this reference. A reference without
+    /// a version accepts anything, an exact version must match exactly and anything
+    /// else is treated as a [`VersionRange`].
+    pub fn matches(&self, version: &str) -> bool {
+        let Some(requirement) = &self.version else {
+            return true;
+        };
+
+        let Ok(version) = semver::Version::parse(version) else {
+            return false;
+        };
+
+        VersionRange::parse(requirement).is_ok_and(|range| range.matches(&version))
+    }
+}
+
+/// A version range in the node-semver syntax the registry resolves dependencies with,
+/// e.g. `^1.2`, `1.x || >=2.1.0 <3` or `1.0.0 - 1.4`.
+///
+/// Ranges are translated into `semver` requirements with the registry's meaning, which
+/// differs for bare versions: `1.0` is `>=1.0.0 <1.1.0` and `1.2.3` only matches
+/// itself. Comma separated comparators are rejected, as the registry does not accept them.
+#[derive(Debug, Clone)]
+pub struct VersionRange {
+    /// Alternatives separated by `||`, any of which has to match.
+    alternatives: Vec<semver::VersionReq>,
+}
+
+impl VersionRange {
+    pub fn parse(range: &str) -> Result<Self, String> {
+        let alternatives = range
+            .split("||")
+            .map(|set| parse_comparator_set(set.trim()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(VersionRange { alternatives })
+    }
+
+    pub fn matches(&self, version: &semver::Version) -> bool {
+        self.alternatives.iter().any(|req| req.matches(version))
+    }
+}
+
+/// Translates the space separated comparators of one `||` alternative.
+fn parse_comparator_set(set: &str) -> Result<semver::VersionReq, String> {
+    if set.contains(',') {
+        return Err(
+            "comparators are separated by spaces, not commas, e.g. '>=1.0.0 <2.0.0'".to_string(),
+        );
+    }
+
+    let tokens = set.split_whitespace().collect::<Vec<_>>();
+
+    let comparators = match tokens.as_slice() {
+        [from, "-", to] => vec![
+            convert_comparator(&format!(">={}", from))?,
+            convert_comparator(&format!("<={}", to))?,
+        ],
+        _ => {
+            // node-semver allows a space after the operator, e.g. `>= 1.2`.
+            let mut joined = Vec::<String>::new();
+            let mut operator = String::new();
+            for token in tokens {
+                match token.chars().all(|c| "<>=^~".contains(c)) {
+                    true => operator.push_str(token),
+                    false => joined.push(format!("{}{}", std::mem::take(&mut operator), token)),
+                }
+            }
+            if !operator.is_empty() {
+                return Err(format!("'{}' is missing a version", operator));
+            }
+
+            joined
+                .iter()
+                .map(|comparator| convert_comparator(comparator))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    let requirement = match comparators.is_empty() {
+        true => "*".to_string(),
+        false => comparators.join(", "),
+    };
+
+    semver::VersionReq::parse(&requirement).map_err(|e| e.to_string())
+}
+
+/// Translates a single comparator like `^1.2`, `1.x` or `<=2` into `semver` syntax.
+fn convert_comparator(comparator: &str) -> Result<String, String> {
+    let version_start = comparator
+        .find(|c: char| !"<>=^~".contains(c))
+        .unwrap_or(comparator.len());
+    let (operator, version) = comparator.split_at(version_start);
+
+    let operator = match operator {
+        "" | "=" => "=",
+        "~>" => "~",
+        ">" | ">=" | "<" | "<=" | "^" | "~" => operator,
+        _ => return Err(format!("invalid operator '{}' in '{}'", operator, comparator)),
+    };
+
+    match partial_version(version)? {
+        Some(version) => Ok(format!("{}{}", operator, version)),
+        None if matches!(operator, ">" | "<") => {
+            Err(format!("'{}' does not match any version", comparator))
+        }
+        None => Ok("*".to_string()),
+    }
+}
+
+/// Parses a full or partial version, dropping wildcard parts: `v1.2.x` gives `1.2`.
+/// Returns `None` for a version that is only a wildcard, like `*` or `x`.
+fn partial_version(version: &str) -> Result<Option<String>, String> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+
+    if semver::Version::parse(version).is_ok() {
+        return Ok(Some(version.to_string()));
+    }
+
+    let is_wildcard = |part: &str| matches!(part, "x" | "X" | "*");
+    let parts = version.split('.').collect::<Vec<_>>();
+    let numbers = parts
+        .iter()
+        .take_while(|part| !is_wildcard(part))
+        .collect::<Vec<_>>();
+
+    let is_valid = parts.len() <= 3
+        && parts[numbers.len()..].iter().all(|part| is_wildcard(part))
+        && numbers
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+
+    if !is_valid {
+        return Err(format!("invalid version '{}'", version));
+    }
+
+    match numbers.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(
+            numbers.iter().map(|part| **part).collect::<Vec<_>>().join("."),
+        )),
+    }
+}
+
+impl fmt::Display for PackageReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}@{}", self.name, version),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+pub fn match_files(pattern: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = vec![];
+
+    for entry in glob(pattern)? {
+        match entry {
+            Ok(path) if path.is_file() => files.push(path),
+            Ok(_) => {}
+            Err(_) => {}
+        }
+    }
+
+    Ok(files)
+}
+
+/// Compiles an `include` pattern to match archive paths the same way [`match_files`]
+/// matches files relative to the package root, e.g. `*.ua` does not match `src/a.ua`.
+pub fn include_pattern(pattern: &str) -> Result<Pattern, PatternError> {
+    Pattern::new(pattern.strip_prefix("./").unwrap_or(pattern))
+}
+
+pub fn include_pattern_matches(pattern: &Pattern, path: &str) -> bool {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+    pattern.matches_with(path, options)
+}
+
+/// Resolves `.` and `..` components of a relative path without touching the file system.
+/// Returns `None` for absolute paths and paths that leave their starting directory.
+pub fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(normalized)
+}
+
+pub fn sha256_hex(buffer: &[u8]) -> String {
+    hex::encode(Sha256::digest(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(range: &str, version: &str) -> bool {
+        VersionRange::parse(range)
+            .unwrap_or_else(|e| panic!("'{}' should parse: {}", range, e))
+            .matches(&semver::Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn bare_partial_versions_are_x_ranges() {
+        assert!(matches("1.0", "1.0.5"));
+        assert!(!matches("1.0", "1.1.0"));
+        assert!(!matches("1.0", "1.5.0"));
+        assert!(matches("1", "1.9.0"));
+        assert!(!matches("1", "2.0.0"));
+        assert!(matches("1.x", "1.4.0"));
+        assert!(matches("1.2.*", "1.2.7"));
+        assert!(matches("*", "3.0.0"));
+        assert!(matches("", "3.0.0"));
+    }
+
+    #[test]
+    fn bare_full_versions_are_exact() {
+        assert!(matches("1.2.3", "1.2.3"));
+        assert!(!matches("1.2.3", "1.2.4"));
+        assert!(matches("v1.2.3", "1.2.3"));
+        assert!(matches("1.2.3-beta.1", "1.2.3-beta.1"));
+    }
+
+    #[test]
+    fn operators_match_node_semver() {
+        assert!(matches("^1.2", "1.9.0"));
+        assert!(!matches("^0.2", "0.3.0"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.9"));
+        assert!(matches("<=1.2", "1.2.9"));
+        assert!(!matches("<=1.2", "1.3.0"));
+        assert!(matches(">= 1.0.0 < 2", "1.5.0"));
+    }
+
+    #[test]
+    fn combines_comparators_alternatives_and_hyphen_ranges() {
+        assert!(matches(">=1.0 <2.0", "1.5.0"));
+        assert!(!matches(">=1.0 <2.0", "2.0.0"));
+        assert!(matches("1.x || 2.x", "2.3.0"));
+        assert!(!matches("1.x || 2.x", "3.0.0"));
+        assert!(matches("1.0.0 - 1.4", "1.4.9"));
+        assert!(!matches("1.0.0 - 1.4", "1.5.0"));
+    }
+
+    #[test]
+    fn rejects_ranges_the_registry_does_not_accept() {
+        assert!(VersionRange::parse(">=1.0, <2.0").is_err());
+        assert!(VersionRange::parse("latest").is_err());
+        assert!(VersionRange::parse(">=").is_err());
+        assert!(VersionRange::parse("1.2.3.4").is_err());
+        assert!(VersionRange::parse("<*").is_err());
+    }
 }
@@ -0,0 +1,136 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const CONFIG_DIR_ENV: &str = "BOO_CONFIG_DIR";
+const CREDENTIALS_FILE_NAME: &str = "credentials.json";
+
+/// The credentials file holds one login per API. Files written by older versions
+/// contain a single login.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CredentialsFile {
+    Logins(Vec<StoredCredentials>),
+    Single(StoredCredentials),
+}
+
+/// An access token saved by `boo login`, reused for later commands.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredCredentials {
+    /// The API the token was issued by, tokens are never sent to a different server.
+    pub api_url: String,
+    pub access_token: String,
+    pub username: String,
+    pub permissions: Vec<String>,
+    pub expires_at: Option<String>,
+}
+
+impl StoredCredentials {
+    pub fn file_path() -> Result<PathBuf, String> {
+        if let Ok(dir) = std::env::var(CONFIG_DIR_ENV) {
+            return Ok(PathBuf::from(dir).join(CREDENTIALS_FILE_NAME));
+        }
+
+        let config_dir = dirs::config_dir().ok_or_else(|| {
+            format!(
+                "Could not determine the config directory, please set {} environment variable.",
+                CONFIG_DIR_ENV
+            )
+        })?;
+
+        Ok(config_dir.join("boo").join(CREDENTIALS_FILE_NAME))
+    }
+
+    /// Loads the stored credentials for the given API, `None` when not logged in to it.
+    pub fn load(api_url: &str) -> Result<Option<Self>, String> {
+        Ok(Self::read_all()?
+            .into_iter()
+            .find(|credentials| credentials.api_url == api_url))
+    }
+
+    /// Stores the credentials, replacing an earlier login to the same API only.
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let mut logins = Self::read_all()?;
+        logins.retain(|credentials| credentials.api_url != self.api_url);
+        logins.push(self.clone());
+        Self::write_all(&logins)
+    }
+
+    /// Removes the stored credentials for the given API, returns `false` if there were none.
+    pub fn delete(api_url: &str) -> Result<bool, String> {
+        let mut logins = Self::read_all()?;
+        let count = logins.len();
+        logins.retain(|credentials| credentials.api_url != api_url);
+        if logins.len() == count {
+            return Ok(false);
+        }
+
+        match logins.is_empty() {
+            true => {
+                let file_path = Self::file_path()?;
+                fs::remove_file(&file_path)
+                    .map_err(|e| format!("Failed to remove '{}': {}", file_path.display(), e))?;
+            }
+            false => {
+                Self::write_all(&logins)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn read_all() -> Result<Vec<Self>, String> {
+        let file_path = Self::file_path()?;
+        if !file_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let file_contents = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read '{}': {}", file_path.display(), e))?;
+
+        let file: CredentialsFile = serde_json::from_str(&file_contents)
+            .map_err(|e| format!("Failed to parse '{}': {}", file_path.display(), e))?;
+
+        Ok(match file {
+            CredentialsFile::Logins(logins) => logins,
+            CredentialsFile::Single(credentials) => vec![credentials],
+        })
+    }
+
+    /// Writes the credentials to disk, readable only by the current user.
+    fn write_all(logins: &[Self]) -> Result<PathBuf, String> {
+        let file_path = Self::file_path()?;
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+
+        let json = serde_json::to_string_pretty(logins)
+            .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options
+            .open(&file_path)
+            .map_err(|e| format!("Failed to open '{}': {}", file_path.display(), e))?;
+
+        // The mode only applies to newly created files, so tighten existing ones too.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&file_path, fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to restrict '{}': {}", file_path.display(), e))?;
+        }
+
+        file.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to write '{}': {}", file_path.display(), e))?;
+
+        Ok(file_path)
+    }
+}
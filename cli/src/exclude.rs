@@ -0,0 +1,274 @@
+//! Decides which of the files matched by the `include` patterns are left out of a package.
+//!
+//! Files are checked against, in this order, the built-in rules, the `exclude` patterns
+//! of `boo.json`, `.booignore` files and, if `gitignore` is enabled, `.gitignore` files.
+//! Ignore files use gitignore semantics and apply to their directory and everything
+//! below it, the one closest to a file taking precedence.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use glob::Pattern;
+use ignore::{
+    Match,
+    gitignore::{Gitignore, GitignoreBuilder},
+};
+
+use crate::common::{self, BooPackageDefinition};
+
+pub const BOOIGNORE_FILE: &str = ".booignore";
+pub const GITIGNORE_FILE: &str = ".gitignore";
+
+/// Directories that never belong in a package: version control and boo's own output.
+const BUILT_IN_EXCLUDES: &[&str] = &[".git", ".boo"];
+
+/// The rule that excluded a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExcludeRule {
+    BuiltIn(&'static str),
+    Pattern(String),
+    IgnoreFile { file: PathBuf, pattern: String },
+}
+
+impl fmt::Display for ExcludeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExcludeRule::BuiltIn(directory) => write!(f, "built-in rule '{}/'", directory),
+            ExcludeRule::Pattern(pattern) => write!(f, "exclude pattern '{}'", pattern),
+            ExcludeRule::IgnoreFile { file, pattern } => {
+                write!(f, "'{}' in {}", pattern, file.display())
+            }
+        }
+    }
+}
+
+/// The compiled `exclude` patterns of `boo.json`, shared by the file collector and the
+/// validation of uploaded packages so both agree on what a pattern matches.
+///
+/// Patterns are globs matched like the lines of a `.booignore` file: a pattern without a
+/// slash matches a file or directory name at any depth, one with a slash is relative to
+/// the package root, and a matching directory excludes everything below it. A trailing
+/// slash only matches directories.
+pub struct ExcludePatterns {
+    patterns: Vec<ExcludePattern>,
+}
+
+struct ExcludePattern {
+    original: String,
+    compiled: Pattern,
+    /// Matched against single names rather than paths from the package root.
+    name_only: bool,
+    directory_only: bool,
+}
+
+impl ExcludePattern {
+    fn new(pattern: &str) -> Result<Self, glob::PatternError> {
+        let directory_only = pattern.ends_with('/');
+        let trimmed = pattern.trim_end_matches('/');
+        let name_only = !trimmed.contains('/');
+        let compiled = common::include_pattern(trimmed.strip_prefix('/').unwrap_or(trimmed))?;
+
+        Ok(ExcludePattern {
+            original: pattern.to_string(),
+            compiled,
+            name_only,
+            directory_only,
+        })
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let directories = path.match_indices('/').map(|(index, _)| &path[..index]);
+        let file = (!self.directory_only).then_some(path);
+
+        directories.chain(file).any(|candidate| {
+            let candidate = match self.name_only {
+                true => candidate.rsplit('/').next().unwrap_or(candidate),
+                false => candidate,
+            };
+            common::include_pattern_matches(&self.compiled, candidate)
+        })
+    }
+}
+
+impl ExcludePatterns {
+    /// Compiles the patterns, returning a message for each invalid one.
+    pub fn new(patterns: &[String]) -> Result<Self, Vec<String>> {
+        let mut compiled_patterns = Vec::new();
+        let mut errors = Vec::new();
+
+        for pattern in patterns {
+            match ExcludePattern::new(pattern) {
+                Ok(compiled) => compiled_patterns.push(compiled),
+                Err(e) => errors.push(format!("Invalid exclude pattern '{}': {}", pattern, e)),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(ExcludePatterns {
+                patterns: compiled_patterns,
+            }),
+            false => Err(errors),
+        }
+    }
+
+    /// Returns the first pattern matching `path`, a file path relative to the package
+    /// root with forward slashes.
+    pub fn matching(&self, path: &str) -> Option<&str> {
+        self.patterns
+            .iter()
+            .find(|pattern| pattern.matches(path))
+            .map(|pattern| pattern.original.as_str())
+    }
+}
+
+pub struct ExcludeRules {
+    patterns: ExcludePatterns,
+    ignore_files: Vec<&'static str>,
+    /// Parsed ignore files by path, `None` if the file does not exist.
+    loaded: HashMap<PathBuf, Option<Gitignore>>,
+    /// Lines of ignore files that could not be parsed, which would silently not exclude
+    /// anything.
+    ignore_file_errors: Vec<String>,
+}
+
+impl ExcludeRules {
+    pub fn new(definition: &BooPackageDefinition) -> Result<Self, String> {
+        let patterns =
+            ExcludePatterns::new(&definition.exclude).map_err(|errors| errors.join("\n"))?;
+
+        let mut ignore_files = vec![BOOIGNORE_FILE];
+        if definition.gitignore {
+            ignore_files.push(GITIGNORE_FILE);
+        }
+
+        Ok(ExcludeRules {
+            patterns,
+            ignore_files,
+            loaded: HashMap::new(),
+            ignore_file_errors: Vec::new(),
+        })
+    }
+
+    /// The errors of all ignore files read so far.
+    pub fn ignore_file_errors(&self) -> &[String] {
+        &self.ignore_file_errors
+    }
+
+    /// Returns the rule that excludes `path`, a file path relative to the package root.
+    pub fn excluded_by(&mut self, path: &Path) -> Option<ExcludeRule> {
+        if let Some(directory) = BUILT_IN_EXCLUDES
+            .iter()
+            .find(|directory| path.starts_with(directory))
+        {
+            return Some(ExcludeRule::BuiltIn(directory));
+        }
+
+        let path_string = path.to_string_lossy().replace('\\', "/");
+        if let Some(pattern) = self.patterns.matching(&path_string) {
+            return Some(ExcludeRule::Pattern(pattern.to_string()));
+        }
+
+        for file_name in self.ignore_files.clone() {
+            match self.match_ignore_files(path, file_name) {
+                Match::None => {}
+                // Whitelisted with `!pattern`, which overrides less specific ignore files.
+                Match::Whitelist(_) => return None,
+                Match::Ignore(rule) => return Some(rule),
+            }
+        }
+
+        None
+    }
+
+    /// Checks the ignore files named `file_name` from the directory of `path` up to the
+    /// package root, returning the match of the closest one with a matching rule.
+    fn match_ignore_files(&mut self, path: &Path, file_name: &str) -> Match<ExcludeRule> {
+        for directory in path.ancestors().skip(1) {
+            let ignore_path = directory.join(file_name);
+            let Some(gitignore) = self.load(&ignore_path) else {
+                continue;
+            };
+
+            let relative_path = path.strip_prefix(directory).unwrap_or(path);
+            let matched = gitignore
+                .matched_path_or_any_parents(relative_path, false)
+                .map(|glob| ExcludeRule::IgnoreFile {
+                    file: ignore_path.clone(),
+                    pattern: glob.original().to_string(),
+                });
+
+            if !matched.is_none() {
+                return matched;
+            }
+        }
+
+        Match::None
+    }
+
+    fn load(&mut self, ignore_path: &Path) -> Option<&Gitignore> {
+        let errors = &mut self.ignore_file_errors;
+        self.loaded
+            .entry(ignore_path.to_path_buf())
+            .or_insert_with(|| {
+                if !ignore_path.is_file() {
+                    return None;
+                }
+
+                // Paths are matched relative to the ignore file's directory.
+                let mut builder = GitignoreBuilder::new(".");
+                // The error already names the file and line.
+                if let Some(e) = builder.add(ignore_path) {
+                    errors.push(format!("Invalid rule in {}", e));
+                }
+                match builder.build() {
+                    Ok(gitignore) => Some(gitignore),
+                    Err(e) => {
+                        errors.push(format!("Failed to read '{}': {}", ignore_path.display(), e));
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> ExcludePatterns {
+        let patterns = patterns.iter().map(|pattern| pattern.to_string()).collect::<Vec<_>>();
+        ExcludePatterns::new(&patterns).unwrap_or_else(|errors| panic!("{:?}", errors))
+    }
+
+    #[test]
+    fn patterns_without_a_slash_match_names_at_any_depth() {
+        let patterns = patterns(&["*.bak"]);
+        assert_eq!(patterns.matching("a.bak"), Some("*.bak"));
+        assert_eq!(patterns.matching("src/deep/a.bak"), Some("*.bak"));
+        assert_eq!(patterns.matching("src/a.ua"), None);
+    }
+
+    #[test]
+    fn directories_exclude_everything_below_them() {
+        let patterns = patterns(&["target/", "docs"]);
+        assert_eq!(patterns.matching("target/x/a.ua"), Some("target/"));
+        assert_eq!(patterns.matching("src/target/a.ua"), Some("target/"));
+        assert_eq!(patterns.matching("target"), None);
+        assert_eq!(patterns.matching("docs/a.md"), Some("docs"));
+        assert_eq!(patterns.matching("docs"), Some("docs"));
+    }
+
+    #[test]
+    fn patterns_with_a_slash_are_relative_to_the_root() {
+        let patterns = patterns(&["/a.ua", "src/*.ua"]);
+        assert_eq!(patterns.matching("a.ua"), Some("/a.ua"));
+        assert_eq!(patterns.matching("lib/a.ua"), None);
+        assert_eq!(patterns.matching("src/b.ua"), Some("src/*.ua"));
+        assert_eq!(patterns.matching("src/deep/b.ua"), None);
+        assert_eq!(patterns.matching("lib/src/b.ua"), None);
+    }
+}
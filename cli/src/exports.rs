@@ -0,0 +1,305 @@
+use std::{fmt, io::Read};
+
+use serde::Serialize;
+use virtual_filesystem::{FileSystem, tar_fs::TarFS};
+
+use crate::imports::find_imports;
+
+/// The public API of a package, i.e. the bindings other packages can use from `lib.ua`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackageApi {
+    pub bindings: Vec<Binding>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BindingKind {
+    /// A function or constant.
+    Binding,
+    /// A binding with a name ending in `!`.
+    Macro,
+    /// A `Name ~ "path"` import or a `┌─╴Name` scoped module.
+    Module,
+    /// A `~Name {...}` data definition.
+    Data,
+}
+
+/// The `|inputs.outputs` signature declared on a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Signature {
+    pub inputs: usize,
+    pub outputs: usize,
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "|{}.{}", self.inputs, self.outputs)
+    }
+}
+
+/// A top-level binding of a Uiua source.
+#[derive(Debug, Clone, Serialize)]
+pub struct Binding {
+    /// The name of the binding. Bindings of scoped modules are prefixed with the module,
+    /// e.g. `Module~Name`.
+    pub name: String,
+    pub kind: BindingKind,
+    pub signature: Option<Signature>,
+    /// The `#` comment lines directly above the binding, without the `#`.
+    pub doc: Option<String>,
+    /// The path a module binding imports, e.g. `boo:@foo/bar` or `src/util.ua`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import: Option<String>,
+    pub line: usize,
+}
+
+/// A change of the public API that breaks packages depending on the previous version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakingChange {
+    Removed(String),
+    SignatureChanged {
+        name: String,
+        previous: Signature,
+        current: Signature,
+    },
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakingChange::Removed(name) => write!(f, "'{}' was removed", name),
+            BreakingChange::SignatureChanged {
+                name,
+                previous,
+                current,
+            } => write!(
+                f,
+                "The signature of '{}' changed from {} to {}",
+                name, previous, current
+            ),
+        }
+    }
+}
+
+impl PackageApi {
+    /// Reads the public bindings of a `lib.ua` source.
+    pub fn from_source(source: &str) -> Self {
+        PackageApi {
+            bindings: find_exports(source),
+        }
+    }
+
+    /// Reads the public bindings of the `lib.ua` in a package archive, `None` if the
+    /// package has no `lib.ua`.
+    pub fn from_archive(buffer: &[u8]) -> Result<Option<Self>, String> {
+        let package = TarFS::new(flate2::read::GzDecoder::new(buffer))
+            .map_err(|e| format!("Failed to read the package: {}", e))?;
+
+        if !package.exists("lib.ua").unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let mut source = String::new();
+        package
+            .open_file("lib.ua")
+            .and_then(|mut file| file.read_to_string(&mut source))
+            .map_err(|e| format!("Failed to read 'lib.ua': {}", e))?;
+
+        Ok(Some(Self::from_source(&source)))
+    }
+
+    /// Lists the bindings of this API that are missing in `current` or whose declared
+    /// signature differs. Bindings without a declared signature are only checked for
+    /// their existence.
+    pub fn breaking_changes(&self, current: &PackageApi) -> Vec<BreakingChange> {
+        let mut changes = Vec::new();
+
+        for previous in &self.bindings {
+            let Some(binding) = current.bindings.iter().find(|b| b.name == previous.name) else {
+                changes.push(BreakingChange::Removed(previous.name.clone()));
+                continue;
+            };
+
+            if let (Some(previous_signature), Some(signature)) = (previous.signature, binding.signature)
+                && previous_signature != signature
+            {
+                changes.push(BreakingChange::SignatureChanged {
+                    name: previous.name.clone(),
+                    previous: previous_signature,
+                    current: signature,
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// Finds all public top-level bindings in a Uiua source.
+///
+/// Only lines starting without indentation are considered outside of scoped modules,
+/// which is how the Uiua formatter writes top-level bindings. Private bindings (`↚`), private imports (`≁`) and
+/// everything inside private modules (`┌╶╶`) are skipped.
+pub fn find_exports(source: &str) -> Vec<Binding> {
+    let mut bindings = Vec::new();
+    let mut doc_lines = Vec::<&str>::new();
+    // Names of the scoped modules the current line is in, `None` for private ones.
+    let mut modules = Vec::<Option<String>>::new();
+
+    for (index, line) in source.lines().enumerate() {
+        // Bindings of scoped modules are indented by the formatter.
+        let line = match modules.is_empty() {
+            true => line.trim_end(),
+            false => line.trim(),
+        };
+
+        if let Some(comment) = line.strip_prefix('#') {
+            doc_lines.push(comment.strip_prefix(' ').unwrap_or(comment));
+            continue;
+        }
+
+        let doc = match doc_lines.is_empty() {
+            true => None,
+            false => Some(doc_lines.join("\n")),
+        };
+        doc_lines.clear();
+
+        if line.starts_with("└─╴") {
+            modules.pop();
+            continue;
+        }
+
+        if line.starts_with("┌╶╶") {
+            modules.push(None);
+            continue;
+        }
+
+        let is_private = modules.iter().any(Option::is_none);
+        let prefix = modules
+            .iter()
+            .flatten()
+            .map(|module| format!("{}~", module))
+            .collect::<String>();
+
+        if let Some(rest) = line.strip_prefix("┌─╴") {
+            let (name, _) = split_identifier(rest.trim_start());
+            if !is_private && !name.is_empty() {
+                bindings.push(Binding {
+                    name: format!("{}{}", prefix, name),
+                    kind: BindingKind::Module,
+                    signature: None,
+                    doc,
+                    import: None,
+                    line: index + 1,
+                });
+            }
+            modules.push((!name.is_empty()).then(|| name.to_string()));
+            continue;
+        }
+
+        if is_private {
+            continue;
+        }
+
+        if let Some(binding) = parse_binding(line) {
+            bindings.push(Binding {
+                name: format!("{}{}", prefix, binding.name),
+                doc,
+                line: index + 1,
+                ..binding
+            });
+        }
+    }
+
+    bindings
+}
+
+/// Parses a binding line, leaving the doc comment and line number for the caller.
+fn parse_binding(line: &str) -> Option<Binding> {
+    let binding = |name: &str, kind| Binding {
+        name: name.to_string(),
+        kind,
+        signature: None,
+        doc: None,
+        import: None,
+        line: 0,
+    };
+
+    // `~Name {...}` or `~Name [...]` data definitions.
+    if let Some(rest) = line.strip_prefix('~') {
+        let (name, _) = split_identifier(rest.trim_start());
+        return (!name.is_empty()).then(|| binding(name, BindingKind::Data));
+    }
+
+    let (name, rest) = split_identifier(line);
+    if name.is_empty() {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let rest = if let Some(rest) = rest.strip_prefix('←') {
+        rest
+    } else if let Some(rest) = rest.strip_prefix('=') {
+        rest
+    } else if let Some(rest) = rest.strip_prefix('~') {
+        let import = find_imports(line).into_iter().next()?;
+        return rest.trim_start().starts_with('"').then(|| Binding {
+            import: Some(import.path),
+            ..binding(name, BindingKind::Module)
+        });
+    } else {
+        return None;
+    };
+
+    let kind = match name.ends_with(['!', '‼']) {
+        true => BindingKind::Macro,
+        false => BindingKind::Binding,
+    };
+
+    Some(Binding {
+        signature: parse_signature(rest.trim_start()),
+        ..binding(name, kind)
+    })
+}
+
+/// Splits a leading Uiua identifier, including subscripts and trailing `!`s, off the text.
+fn split_identifier(text: &str) -> (&str, &str) {
+    let mut end = 0;
+    for (index, c) in text.char_indices() {
+        let is_valid = match index {
+            0 => c.is_alphabetic(),
+            _ => c.is_alphabetic() || ('₀'..='₉').contains(&c) || c == '₋',
+        };
+        if !is_valid {
+            break;
+        }
+        end = index + c.len_utf8();
+    }
+
+    if end > 0 {
+        end += text[end..].len() - text[end..].trim_start_matches(['!', '‼']).len();
+    }
+
+    text.split_at(end)
+}
+
+/// Parses a `|2` or `|2.1` signature at the start of a binding's body.
+fn parse_signature(body: &str) -> Option<Signature> {
+    let body = body.strip_prefix('|')?;
+    let inputs_end = body
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(body.len());
+    let inputs = body[..inputs_end].parse().ok()?;
+
+    let outputs = match body[inputs_end..].strip_prefix('.') {
+        Some(rest) => {
+            let outputs_end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            rest[..outputs_end].parse().ok()?
+        }
+        None => 1,
+    };
+
+    Some(Signature { inputs, outputs })
+}
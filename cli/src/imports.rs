@@ -0,0 +1,189 @@
+use std::ops::Range;
+
+pub const BOO_IMPORT_PREFIX: &str = "boo:";
+
+/// A `~ "path"` import found in a Uiua source.
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub path: String,
+    /// Byte range of the string literal in the source, including the quotes.
+    pub span: Range<usize>,
+    pub line: usize,
+}
+
+impl Import {
+    /// The package reference of a `boo:` import, without the prefix.
+    pub fn boo_reference(&self) -> Option<&str> {
+        self.path.strip_prefix(BOO_IMPORT_PREFIX)
+    }
+}
+
+/// Finds all imports in a Uiua source.
+///
+/// Only string literals directly following a `~` are considered imports. Comments,
+/// character literals, raw strings (`$ ...`) and format strings are skipped, so a
+/// `"boo:..."` string anywhere else in the code is never mistaken for an import.
+pub fn find_imports(source: &str) -> Vec<Import> {
+    let mut imports = vec![];
+    let mut chars = source.char_indices().peekable();
+    let mut line = 1;
+    let mut after_tilde = false;
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\n' => {
+                line += 1;
+                after_tilde = false;
+            }
+            c if c.is_whitespace() => {}
+            '~' => after_tilde = true,
+            '#' => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                after_tilde = false;
+            }
+            '$' if chars.peek().is_none_or(|(_, c)| *c == ' ' || *c == '\n') => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                after_tilde = false;
+            }
+            '@' => {
+                match chars.next() {
+                    Some((_, '\\')) => {
+                        chars.next();
+                    }
+                    Some((_, '\n')) => line += 1,
+                    _ => {}
+                }
+                after_tilde = false;
+            }
+            '"' => {
+                let mut path = String::new();
+                let mut end = None;
+
+                while let Some((index, c)) = chars.next_if(|(_, c)| *c != '\n') {
+                    match c {
+                        '"' => {
+                            end = Some(index + 1);
+                            break;
+                        }
+                        '\\' => match chars.next_if(|(_, c)| *c != '\n') {
+                            Some((_, 'n')) => path.push('\n'),
+                            Some((_, 't')) => path.push('\t'),
+                            Some((_, escaped)) => path.push(escaped),
+                            None => {}
+                        },
+                        c => path.push(c),
+                    }
+                }
+
+                if let Some(end) = end
+                    && after_tilde
+                {
+                    imports.push(Import {
+                        path,
+                        span: start..end,
+                        line,
+                    });
+                }
+                after_tilde = false;
+            }
+            _ => after_tilde = false,
+        }
+    }
+
+    imports
+}
+
+/// Finds the package references of all `~ "boo:..."` imports in a Uiua source,
+/// without the `boo:` prefix.
+pub fn find_boo_imports(source: &str) -> Vec<String> {
+    find_imports(source)
+        .iter()
+        .filter_map(|import| import.boo_reference().map(str::to_string))
+        .collect()
+}
+
+/// Derives the identifier to bind a package's import to, e.g. `@foo/my-lib-2` becomes `MyLib₋₂`.
+///
+/// This mirrors `uiuaifyNameToIdentifier` on the server: trailing digits turn into
+/// subscripts, the rest of the name is converted to PascalCase and any remaining
+/// digits are dropped, since Uiua identifiers cannot contain them.
+pub fn import_identifier(package_name: &str) -> String {
+    let name = package_name
+        .rsplit_once('/')
+        .map_or(package_name, |(_, name)| name);
+
+    let digits_start = name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (mut base, digits) = name.split_at(digits_start);
+
+    let mut suffix = String::new();
+    if !digits.is_empty() {
+        if let Some(stripped) = base.strip_suffix('-') {
+            base = stripped;
+            suffix.push('₋');
+        }
+        suffix.extend(digits.chars().map(to_subscript));
+    }
+
+    let base = match base.chars().any(|c| c.is_lowercase()) {
+        true => base.to_string(),
+        false => base.to_lowercase(),
+    };
+
+    let mut identifier = String::new();
+    let mut at_word_start = true;
+    for c in base.chars() {
+        if c.is_alphabetic() {
+            match at_word_start {
+                true => identifier.extend(c.to_uppercase()),
+                false => identifier.push(c),
+            }
+        }
+        // Digits are dropped, but like letters they do not start a new word.
+        at_word_start = !c.is_alphanumeric();
+    }
+
+    identifier + &suffix
+}
+
+fn to_subscript(digit: char) -> char {
+    match digit {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_numbers_become_subscripts() {
+        assert_eq!(import_identifier("@scope/my-lib-2"), "MyLib₋₂");
+        assert_eq!(import_identifier("@scope/my-lib2"), "MyLib₂");
+        assert_eq!(import_identifier("@scope/lib-10"), "Lib₋₁₀");
+    }
+
+    #[test]
+    fn names_are_pascal_cased() {
+        assert_eq!(import_identifier("@scope/my_cool-lib"), "MyCoolLib");
+        assert_eq!(import_identifier("@scope/myLib"), "MyLib");
+        assert_eq!(import_identifier("@scope/HTTP"), "Http");
+        assert_eq!(import_identifier("@scope/HTTP-2"), "Http₋₂");
+    }
+
+    #[test]
+    fn digits_inside_the_name_are_dropped() {
+        assert_eq!(import_identifier("@scope/b64-codec"), "BCodec");
+        assert_eq!(import_identifier("@scope/my2lib"), "Mylib");
+        assert_eq!(import_identifier("@scope/lib-2d-3"), "Libd₋₃");
+    }
+}
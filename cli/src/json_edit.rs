@@ -0,0 +1,330 @@
+//! Minimal in-place editing of JSON documents such as `boo.json`.
+//!
+//! Instead of re-serializing the whole document, edits are applied to the
+//! original text, so the key order, indentation and everything else the
+//! user wrote stays untouched.
+
+use std::ops::Range;
+
+struct Member {
+    key: String,
+    /// Byte range from the opening quote of the key to the end of the value.
+    span: Range<usize>,
+    value: Range<usize>,
+}
+
+struct Object {
+    /// Byte index of the opening brace.
+    open: usize,
+    /// Byte index of the closing brace.
+    close: usize,
+    members: Vec<Member>,
+}
+
+impl Object {
+    fn get(&self, key: &str) -> Option<&Member> {
+        self.members.iter().find(|member| member.key == key)
+    }
+
+    /// The whitespace before the first member.
+    fn member_indent<'a>(&self, source: &'a str) -> &'a str {
+        match self.members.first() {
+            Some(first) => &source[self.open + 1..first.span.start],
+            None => "",
+        }
+    }
+
+    /// The whitespace after the comma between members, taken from the existing members.
+    fn member_separator(&self, source: &str) -> String {
+        match self.members.as_slice() {
+            [first, second, ..] => source[first.span.end..second.span.start].replacen(',', "", 1),
+            _ => match self.member_indent(source) {
+                indent if indent.contains('\n') => indent.to_string(),
+                _ => " ".to_string(),
+            },
+        }
+    }
+}
+
+/// Sets the top-level `key` to the JSON string `value`, updating it in place if it exists
+/// and appending it after the last member otherwise.
+pub fn set_string(source: &str, key: &str, value: &str) -> Result<String, String> {
+    let root = parse_document(source)?;
+    let new_value = to_json_string(value);
+
+    match root.get(key) {
+        Some(existing) => {
+            let mut result = source.to_string();
+            result.replace_range(existing.value.clone(), &new_value);
+            Ok(result)
+        }
+        None => Ok(insert_member(source, &root, key, &new_value)),
+    }
+}
+
+/// Sets `key` to the JSON string `value` inside the object at the top-level `object_key`,
+/// creating the object if it does not exist yet. Existing keys are updated in place,
+/// new keys are appended after the last member.
+pub fn set_nested_string(
+    source: &str,
+    object_key: &str,
+    key: &str,
+    value: &str,
+) -> Result<String, String> {
+    let root = parse_document(source)?;
+    let new_value = to_json_string(value);
+
+    let Some(member) = root.get(object_key) else {
+        let indent = root.member_indent(source);
+        let unit = indent_unit(indent);
+        let nested = format!(
+            "{{{indent}{unit}{}: {}{indent}}}",
+            to_json_string(key),
+            new_value
+        );
+        return Ok(insert_member(source, &root, object_key, &nested));
+    };
+
+    let object = parse_object_at(source, member.value.start)
+        .map_err(|_| format!("'{}' must be an object", object_key))?;
+
+    if let Some(existing) = object.get(key) {
+        let mut result = source.to_string();
+        result.replace_range(existing.value.clone(), &new_value);
+        return Ok(result);
+    }
+
+    if object.members.is_empty() {
+        let indent = root.member_indent(source);
+        let unit = indent_unit(indent);
+        let mut result = source.to_string();
+        result.replace_range(
+            object.open + 1..object.close,
+            &format!("{indent}{unit}{}: {}{indent}", to_json_string(key), new_value),
+        );
+        return Ok(result);
+    }
+
+    Ok(insert_member(source, &object, key, &new_value))
+}
+
+/// Removes `key` from the object at the top-level `object_key`.
+/// Returns `None` when there was nothing to remove.
+pub fn remove_nested_key(source: &str, object_key: &str, key: &str) -> Result<Option<String>, String> {
+    let root = parse_document(source)?;
+
+    let Some(member) = root.get(object_key) else {
+        return Ok(None);
+    };
+
+    let object = parse_object_at(source, member.value.start)
+        .map_err(|_| format!("'{}' must be an object", object_key))?;
+
+    let Some(index) = object.members.iter().position(|member| member.key == key) else {
+        return Ok(None);
+    };
+
+    let removed = if object.members.len() == 1 {
+        object.open + 1..object.close
+    } else if index == 0 {
+        object.members[0].span.start..object.members[1].span.start
+    } else {
+        object.members[index - 1].span.end..object.members[index].span.end
+    };
+
+    let mut result = source.to_string();
+    result.replace_range(removed, "");
+    Ok(Some(result))
+}
+
+fn insert_member(source: &str, object: &Object, key: &str, value: &str) -> String {
+    let member = format!("{}: {}", to_json_string(key), value);
+    let mut result = source.to_string();
+
+    match object.members.last() {
+        Some(last) => {
+            let separator = object.member_separator(source);
+            result.insert_str(last.span.end, &format!(",{}{}", separator, member));
+        }
+        None => result.insert_str(object.open + 1, &member),
+    }
+
+    result
+}
+
+/// The indentation of a single nesting level, derived from the indentation of a top-level member.
+fn indent_unit(indent: &str) -> &str {
+    match indent.rfind('\n') {
+        Some(index) => &indent[index + 1..],
+        None => "",
+    }
+}
+
+fn to_json_string(value: &str) -> String {
+    serde_json::to_string(value).expect("Strings can always be serialized")
+}
+
+fn parse_document(source: &str) -> Result<Object, String> {
+    serde_json::from_str::<serde_json::Value>(source).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let start = skip_whitespace(source, 0);
+    parse_object_at(source, start).map_err(|_| "The document must be a JSON object".to_string())
+}
+
+/// Parses the object starting at `start`. The source must already be known to be valid JSON.
+fn parse_object_at(source: &str, start: usize) -> Result<Object, ()> {
+    let bytes = source.as_bytes();
+    if bytes.get(start) != Some(&b'{') {
+        return Err(());
+    }
+
+    let mut members = vec![];
+    let mut position = skip_whitespace(source, start + 1);
+
+    while bytes[position] != b'}' {
+        let key_start = position;
+        let key_end = skip_string(source, key_start);
+        let key: String = serde_json::from_str(&source[key_start..key_end]).map_err(|_| ())?;
+
+        // Skip the colon between the key and the value.
+        let value_start = skip_whitespace(source, skip_whitespace(source, key_end) + 1);
+        let value_end = skip_value(source, value_start);
+
+        members.push(Member {
+            key,
+            span: key_start..value_end,
+            value: value_start..value_end,
+        });
+
+        position = skip_whitespace(source, value_end);
+        if bytes[position] == b',' {
+            position = skip_whitespace(source, position + 1);
+        }
+    }
+
+    Ok(Object {
+        open: start,
+        close: position,
+        members,
+    })
+}
+
+fn skip_whitespace(source: &str, mut position: usize) -> usize {
+    let bytes = source.as_bytes();
+    while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+        position += 1;
+    }
+    position
+}
+
+fn skip_string(source: &str, start: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut position = start + 1;
+    while bytes[position] != b'"' {
+        if bytes[position] == b'\\' {
+            position += 1;
+        }
+        position += 1;
+    }
+    position + 1
+}
+
+fn skip_value(source: &str, start: usize) -> usize {
+    let bytes = source.as_bytes();
+    match bytes[start] {
+        b'"' => skip_string(source, start),
+        b'{' | b'[' => {
+            let mut depth = 0;
+            let mut position = start;
+            loop {
+                match bytes[position] {
+                    b'"' => {
+                        position = skip_string(source, position);
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return position + 1;
+                        }
+                    }
+                    _ => {}
+                }
+                position += 1;
+            }
+        }
+        _ => {
+            let mut position = start;
+            while position < bytes.len()
+                && !matches!(bytes[position], b',' | b'}' | b']')
+                && !bytes[position].is_ascii_whitespace()
+            {
+                position += 1;
+            }
+            position
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPENDENCIES: &str = "dependencies";
+
+    #[test]
+    fn adds_a_missing_dependencies_object() {
+        let source = "{\n  \"name\": \"@ab/cd\"\n}\n";
+        let updated = set_nested_string(source, DEPENDENCIES, "@ab/ef", "^1.0.0").unwrap();
+        assert_eq!(
+            updated,
+            "{\n  \"name\": \"@ab/cd\",\n  \"dependencies\": {\n    \"@ab/ef\": \"^1.0.0\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn adds_to_an_empty_dependencies_object() {
+        let source = "{\n  \"name\": \"@ab/cd\",\n  \"dependencies\": {}\n}\n";
+        let updated = set_nested_string(source, DEPENDENCIES, "@ab/ef", "^1.0.0").unwrap();
+        assert_eq!(
+            updated,
+            "{\n  \"name\": \"@ab/cd\",\n  \"dependencies\": {\n    \"@ab/ef\": \"^1.0.0\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn adds_to_and_updates_a_filled_dependencies_object() {
+        let source = "{\n  \"dependencies\": {\n    \"@ab/ef\": \"^1.0.0\"\n  }\n}";
+
+        let added = set_nested_string(source, DEPENDENCIES, "@ab/gh", "~2.1").unwrap();
+        assert_eq!(
+            added,
+            "{\n  \"dependencies\": {\n    \"@ab/ef\": \"^1.0.0\",\n    \"@ab/gh\": \"~2.1\"\n  }\n}"
+        );
+
+        let updated = set_nested_string(source, DEPENDENCIES, "@ab/ef", "^1.2.0").unwrap();
+        assert_eq!(updated, "{\n  \"dependencies\": {\n    \"@ab/ef\": \"^1.2.0\"\n  }\n}");
+    }
+
+    #[test]
+    fn removes_the_first_middle_and_last_dependency() {
+        let source = "{\"dependencies\": {\n  \"@a/a\": \"1\",\n  \"@a/b\": \"2\",\n  \"@a/c\": \"3\"\n}}";
+        let remove = |key| remove_nested_key(source, DEPENDENCIES, key).unwrap().unwrap();
+
+        assert_eq!(remove("@a/a"), "{\"dependencies\": {\n  \"@a/b\": \"2\",\n  \"@a/c\": \"3\"\n}}");
+        assert_eq!(remove("@a/b"), "{\"dependencies\": {\n  \"@a/a\": \"1\",\n  \"@a/c\": \"3\"\n}}");
+        assert_eq!(remove("@a/c"), "{\"dependencies\": {\n  \"@a/a\": \"1\",\n  \"@a/b\": \"2\"\n}}");
+    }
+
+    #[test]
+    fn removes_the_only_dependency_and_ignores_missing_ones() {
+        let source = "{\"dependencies\": {\"@a/a\": \"1\"}}";
+        assert_eq!(
+            remove_nested_key(source, DEPENDENCIES, "@a/a").unwrap(),
+            Some("{\"dependencies\": {}}".to_string())
+        );
+        assert_eq!(remove_nested_key(source, DEPENDENCIES, "@a/b").unwrap(), None);
+        assert_eq!(remove_nested_key("{}", DEPENDENCIES, "@a/a").unwrap(), None);
+    }
+}
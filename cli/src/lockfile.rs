@@ -0,0 +1,69 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+pub const LOCKFILE_NAME: &str = "boo.lock";
+const LOCKFILE_VERSION: u32 = 1;
+
+/// The contents of `boo.lock`: the exact versions of every package the project
+/// depends on, including transitive dependencies, keyed by package name.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Lockfile {
+    pub version: u32,
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedPackage {
+    pub version: String,
+    pub download_url: String,
+    /// Hex encoded SHA-256 of the downloaded archive.
+    pub checksum: String,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+    pub fn new() -> Self {
+        Lockfile {
+            version: LOCKFILE_VERSION,
+            packages: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the lockfile, returning `None` when it does not exist yet.
+    pub fn read_from_file(file_path: &Path) -> Result<Option<Self>, String> {
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let file_contents = fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to read {}: {}", LOCKFILE_NAME, e))?;
+
+        let lockfile: Lockfile = serde_json::from_str(&file_contents)
+            .map_err(|e| format!("Failed to parse {}: {}", LOCKFILE_NAME, e))?;
+
+        if lockfile.version != LOCKFILE_VERSION {
+            return Err(format!(
+                "Unsupported {} version {}, expected {}. Run `boo install --update` to regenerate it.",
+                LOCKFILE_NAME, lockfile.version, LOCKFILE_VERSION
+            ));
+        }
+
+        Ok(Some(lockfile))
+    }
+
+    pub fn write_to_file(&self, file_path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize {}: {}", LOCKFILE_NAME, e))?;
+
+        fs::write(file_path, json + "\n")
+            .map_err(|e| format!("Failed to write to {}: {}", LOCKFILE_NAME, e))
+    }
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
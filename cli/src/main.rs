@@ -1,30 +1,71 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use owo_colors::OwoColorize;
 
 pub mod common;
 pub mod api;
+pub mod auth;
+pub mod cache;
+pub mod credentials;
+pub mod exclude;
+pub mod exports;
+pub mod imports;
+pub mod json_edit;
+pub mod lockfile;
+pub mod output;
+pub mod resolver;
 mod commands {
+    pub mod build;
+    pub mod dependency;
+    pub mod docs;
+    pub mod exports;
     pub mod init;
+    pub mod inspect;
+    pub mod install;
+    pub mod login;
+    pub mod pack;
     pub mod publish;
     pub mod validate;
+    pub mod version;
 }
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(Parser, Debug)]
-#[clap(name = "boo", version = VERSION, about = "Uiua package manager")]
+#[clap(
+    name = "boo",
+    version = VERSION,
+    about = "Uiua package manager",
+    after_help = "Find out more at https://uiua.boo/"
+)]
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
+    #[clap(
+        long,
+        global = true,
+        help = "Print output as JSON, one event per line."
+    )]
+    json: bool,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     Init(InitArgs),
+    Install(InstallArgs),
+    Add(AddArgs),
+    Remove(RemoveArgs),
+    Build(BuildArgs),
+    Run(RunArgs),
+    Login(LoginArgs),
+    Logout,
+    Whoami,
+    Pack(PackArgs),
     Publish(PublishArgs),
     Validate(ValidationArgs),
-    Docs,
-    Version,
+    Inspect(InspectArgs),
+    Exports(ExportsArgs),
+    Docs(DocsArgs),
+    Version(VersionArgs),
 }
 
 #[derive(Args, Debug)]
@@ -34,6 +75,61 @@ struct InitArgs {
 }
 
 #[derive(Args, Debug)]
+struct InstallArgs {
+    #[clap(
+        long,
+        help = "Ignore boo.lock and resolve the latest compatible versions again."
+    )]
+    update: bool,
+}
+
+#[derive(Args, Debug)]
+struct AddArgs {
+    #[clap(help = "The package to add, optionally with a version range, e.g. '@foo/bar@^1.2'.")]
+    package: String,
+}
+
+#[derive(Args, Debug)]
+struct RemoveArgs {
+    #[clap(help = "The name of the package to remove, e.g. '@foo/bar'.")]
+    package: String,
+}
+
+#[derive(Args, Debug)]
+struct BuildArgs {
+    #[clap(default_value = "main.ua", help = "The file to build.")]
+    entry: String,
+    #[clap(
+        long,
+        default_value = ".boo/build",
+        help = "Directory to write the built project to."
+    )]
+    out_dir: String,
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    #[clap(flatten)]
+    build: BuildArgs,
+    #[clap(last = true, help = "Arguments passed to the Uiua program.")]
+    args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct LoginArgs {
+    #[clap(
+        long,
+        help = "Scope to allow publishing to, e.g. '@foo'. Can be repeated, defaults to the scope of the current package."
+    )]
+    scope: Vec<String>,
+    #[clap(long, default_value_t = 30, help = "Number of days until the token expires.")]
+    days: u64,
+}
+
+#[derive(Args, Debug)]
+#[clap(
+    after_help = "Exit codes: 1 other errors, 2 validation failed, 3 authorization failed, 4 network error, 5 publishing job failed."
+)]
 struct PublishArgs {
     #[clap(
         long,
@@ -42,6 +138,44 @@ struct PublishArgs {
     check: bool,
     #[clap(long, help = "Output the package to a file instead of uploading it.")]
     offline: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["offline", "show_excluded"],
+        help = "Upload a previously built package archive instead of packing the current directory."
+    )]
+    archive: Option<String>,
+    #[clap(
+        long,
+        help = "Read the access token from a file instead of asking for authorization. The BOO_TOKEN environment variable can be used as well."
+    )]
+    token_file: Option<String>,
+    #[clap(
+        long,
+        help = "Only warn about removed bindings or changed signatures that would require a new major version."
+    )]
+    allow_breaking: bool,
+    #[clap(
+        long,
+        help = "List the files matched by 'include' that were left out, and the rule that excluded each."
+    )]
+    show_excluded: bool,
+}
+
+#[derive(Args, Debug)]
+#[clap(
+    about = "Build the package archive that `publish` would upload and write it to <name>-<version>.tar.gz."
+)]
+struct PackArgs {
+    #[clap(
+        long,
+        help = "List the archived files with their sizes and the compression ratio instead of writing the archive."
+    )]
+    list: bool,
+    #[clap(
+        long,
+        help = "List the files matched by 'include' that were left out, and the rule that excluded each."
+    )]
+    show_excluded: bool,
 }
 
 #[derive(Args, Debug)]
@@ -51,36 +185,103 @@ struct ValidationArgs {
     expect_name: Option<String>,
     #[clap(long, help = "Expected package version for validation.")]
     expect_version: Option<String>,
-    #[clap(long, help = "Print validation results in JSON format.")]
-    json: bool, 
+}
+
+#[derive(Args, Debug)]
+#[clap(about = "Print the manifest, entries and checksum of a package archive without extracting it.")]
+struct InspectArgs {
+    package_file: String,
+}
+
+#[derive(Args, Debug)]
+#[clap(about = "Bump the version in boo.json. Use `--version` for the version of boo itself.")]
+struct VersionArgs {
+    bump: VersionBump,
+    #[clap(
+        long,
+        help = "Identifier of the pre-release, e.g. 'rc'. Defaults to the current one or 'beta'."
+    )]
+    preid: Option<String>,
+    #[clap(long, help = "Commit boo.json and tag the commit as v<version>.")]
+    git_tag: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+}
+
+#[derive(Args, Debug)]
+#[clap(about = "Generate HTML documentation for the package.")]
+struct DocsArgs {
+    #[clap(
+        long,
+        default_value = ".boo/docs",
+        help = "Directory to write the documentation to."
+    )]
+    out_dir: String,
+    #[clap(
+        long,
+        help = "Serve the documentation locally and regenerate it when the package changes."
+    )]
+    serve: bool,
+    #[clap(long, default_value_t = 8080, help = "Port to serve the documentation on.")]
+    port: u16,
+}
+
+#[derive(Args, Debug)]
+#[clap(about = "List the public bindings of lib.ua with their signatures and doc comments.")]
+struct ExportsArgs {
+    #[clap(help = "Read lib.ua from a package archive instead of the current directory.")]
+    package_file: Option<String>,
 }
 
 fn main() {
     env_logger::init();
     
     let cli = Cli::parse();
+    output::set_json(cli.json);
+
     match cli.command {
         Commands::Init(args) => commands::init::run_init(args),
+        Commands::Install(args) => commands::install::run_install(args),
+        Commands::Add(args) => commands::dependency::run_add(args),
+        Commands::Remove(args) => commands::dependency::run_remove(args),
+        Commands::Build(args) => commands::build::run_build(args),
+        Commands::Run(args) => commands::build::run_run(args),
+        Commands::Login(args) => commands::login::run_login(args),
+        Commands::Logout => commands::login::run_logout(),
+        Commands::Whoami => commands::login::run_whoami(),
+        Commands::Pack(args) => commands::pack::run_pack(args),
         Commands::Publish(args) => commands::publish::run_publish(args),
         Commands::Validate(args) => commands::validate::run_validation(args),
-        Commands::Docs => {
-            panic!("TODO: Implement docs command");
-        }
-        Commands::Version => {
-            println!("Boo v{} - Uiua package manager.", VERSION);
-            println!("Find out more at https://uiua.boo/");
-        }
+        Commands::Inspect(args) => commands::inspect::run_inspect(args),
+        Commands::Exports(args) => commands::exports::run_exports(args),
+        Commands::Docs(args) => commands::docs::run_docs(args),
+        Commands::Version(args) => commands::version::run_version(args),
     }
 }
 
 pub(crate) fn print_success(message: &str) {
+    if output::is_json() {
+        return output::emit_message("success", message);
+    }
     println!("{} {}", "[OK]".green(), message.green());
 }
 
 pub(crate) fn print_warning(message: &str) {
+    if output::is_json() {
+        return output::emit_message("warning", message);
+    }
     println!("{} {}", "[WARNING]".yellow(), message.yellow());
 }
 
 pub(crate) fn print_error(message: &str) {
+    if output::is_json() {
+        return output::emit_message("error", message);
+    }
     println!("{} {}", "[ERROR]".red(), message.red());
 }
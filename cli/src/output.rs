@@ -0,0 +1,48 @@
+//! Switches command output between human readable text and JSON.
+//!
+//! With `--json`, every message is written to stdout as a single JSON object per
+//! line, e.g. `{"event":"warning","message":"..."}`. Commands emit additional
+//! events with structured data that tooling can rely on, like `published`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_json(enabled: bool) {
+    JSON_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Writes a structured event in JSON mode, does nothing otherwise.
+///
+/// The fields of `data` are added next to the `event` name, so `data` should
+/// serialize to an object. Anything else is placed in a `data` field.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    if !is_json() {
+        return;
+    }
+
+    let mut object = Map::new();
+    object.insert("event".to_string(), Value::String(event.to_string()));
+
+    match serde_json::to_value(data).expect("Events can always be serialized") {
+        Value::Object(fields) => object.extend(fields),
+        Value::Null => {}
+        other => {
+            object.insert("data".to_string(), other);
+        }
+    }
+
+    println!("{}", Value::Object(object));
+}
+
+/// Emits a `success`, `warning` or `error` event carrying only a message.
+pub fn emit_message(event: &str, message: &str) {
+    emit(event, serde_json::json!({ "message": message }));
+}
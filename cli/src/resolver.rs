@@ -0,0 +1,379 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
+};
+
+use semver::Version;
+
+use crate::{
+    api::{BooApiClient, PackageVersionInfo},
+    common::PackageReference,
+};
+
+/// All published versions of every package reachable from the root requirements,
+/// sorted from the newest to the oldest version.
+pub struct RegistryIndex {
+    packages: BTreeMap<String, Vec<(Version, PackageVersionInfo)>>,
+}
+
+impl RegistryIndex {
+    pub async fn fetch(
+        client: &BooApiClient,
+        references: &BTreeSet<PackageReference>,
+    ) -> Result<Self, String> {
+        let mut packages = BTreeMap::new();
+        let mut queue = VecDeque::from_iter(references.iter().map(|r| r.name.clone()));
+
+        while let Some(name) = queue.pop_front() {
+            if packages.contains_key(&name) {
+                continue;
+            }
+
+            // Old versions may depend on packages that do not exist. That only matters
+            // if such a version is picked, which the resolver reports as a conflict.
+            let response = client
+                .find_package_versions(&name)
+                .await
+                .map_err(|e| format!("Failed to fetch the versions of {}: {}", name, e))?;
+
+            let mut versions = vec![];
+            for info in response.map(|response| response.versions).unwrap_or_default() {
+                let Ok(version) = Version::parse(&info.version) else {
+                    continue;
+                };
+
+                queue.extend(info.dependencies.keys().cloned());
+                versions.push((version, info));
+            }
+
+            versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+            packages.insert(name, versions);
+        }
+
+        Ok(RegistryIndex { packages })
+    }
+
+    fn versions(&self, name: &str) -> &[(Version, PackageVersionInfo)] {
+        self.packages.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Explains why no consistent set of versions exists, as the chains of
+/// requirements that lead to the conflict.
+#[derive(Debug)]
+pub struct ResolutionConflict {
+    pub summary: String,
+    pub reasons: Vec<String>,
+}
+
+impl fmt::Display for ResolutionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary)?;
+        for reason in &self.reasons {
+            write!(f, "\n- {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Requirement {
+    reference: PackageReference,
+    /// The package that declared this requirement, `None` for the root package.
+    required_by: Option<String>,
+}
+
+/// Picks one version of every package so that all requirements are satisfied,
+/// preferring the newest versions and backtracking when a choice leads to a conflict.
+///
+/// Pre-releases follow the registry's rules: they are only picked when a requirement
+/// names the exact version, ranges and unversioned references only match stable versions.
+pub struct Resolver<'a> {
+    index: &'a RegistryIndex,
+    root_name: String,
+    preferred_versions: BTreeMap<String, String>,
+    selected: BTreeMap<String, (Version, Requirement)>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(index: &'a RegistryIndex, root_name: &str) -> Self {
+        Resolver {
+            index,
+            root_name: root_name.to_string(),
+            preferred_versions: BTreeMap::new(),
+            selected: BTreeMap::new(),
+        }
+    }
+
+    /// Versions to try first when they satisfy the requirements, e.g. the ones from `boo.lock`.
+    pub fn with_preferred_versions(mut self, preferred_versions: BTreeMap<String, String>) -> Self {
+        self.preferred_versions = preferred_versions;
+        self
+    }
+
+    pub fn resolve(
+        mut self,
+        references: &BTreeSet<PackageReference>,
+    ) -> Result<BTreeMap<String, PackageVersionInfo>, ResolutionConflict> {
+        let pending = references
+            .iter()
+            .map(|reference| Requirement {
+                reference: reference.clone(),
+                required_by: None,
+            })
+            .collect();
+
+        self.solve(pending)?;
+
+        let mut resolved = BTreeMap::new();
+        for (name, (version, _)) in &self.selected {
+            let info = self
+                .index
+                .versions(name)
+                .iter()
+                .find(|(v, _)| v == version)
+                .map(|(_, info)| info.clone())
+                .expect("Selected version must exist in the index");
+
+            resolved.insert(name.clone(), info);
+        }
+
+        Ok(resolved)
+    }
+
+    fn solve(&mut self, mut pending: VecDeque<Requirement>) -> Result<(), ResolutionConflict> {
+        let Some(requirement) = pending.pop_front() else {
+            return Ok(());
+        };
+
+        let name = requirement.reference.name.clone();
+
+        if let Some((version, selected_by)) = self.selected.get(&name) {
+            if is_satisfied_by(&requirement.reference, version) {
+                return self.solve(pending);
+            }
+
+            let mut reasons = self.explain(selected_by);
+            if let Some(last) = reasons.last_mut() {
+                *last = format!("{}, which selected version {}", last, version);
+            }
+            reasons.extend(self.explain(&requirement));
+
+            return Err(ResolutionConflict {
+                summary: format!("Conflicting requirements for {}:", name),
+                reasons,
+            });
+        }
+
+        if self.index.versions(&name).is_empty() {
+            return Err(ResolutionConflict {
+                summary: format!("Package {} is not published:", name),
+                reasons: self.explain(&requirement),
+            });
+        }
+
+        let candidates = self.candidates(&requirement.reference);
+        if candidates.is_empty() {
+            return Err(ResolutionConflict {
+                summary: format!(
+                    "No published version of {} matches {}:",
+                    name,
+                    describe_version(&requirement.reference)
+                ),
+                reasons: self.explain(&requirement),
+            });
+        }
+
+        let mut last_conflict = None;
+        for (version, info) in candidates {
+            self.selected
+                .insert(name.clone(), (version.clone(), requirement.clone()));
+
+            let mut next = pending.clone();
+            next.extend(info.dependencies.iter().map(|(dependency, range)| Requirement {
+                reference: PackageReference {
+                    name: dependency.clone(),
+                    version: Some(range.clone()),
+                },
+                required_by: Some(name.clone()),
+            }));
+
+            match self.solve(next) {
+                Ok(()) => return Ok(()),
+                Err(conflict) => last_conflict = Some(conflict),
+            }
+
+            self.selected.remove(&name);
+        }
+
+        Err(last_conflict.expect("At least one candidate was tried"))
+    }
+
+    fn candidates(&self, reference: &PackageReference) -> Vec<(Version, PackageVersionInfo)> {
+        let mut candidates = self
+            .index
+            .versions(&reference.name)
+            .iter()
+            .filter(|(version, _)| is_satisfied_by(reference, version))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if let Some(preferred) = self.preferred_versions.get(&reference.name)
+            && let Some(position) = candidates.iter().position(|(v, _)| v.to_string() == *preferred)
+        {
+            let preferred = candidates.remove(position);
+            candidates.insert(0, preferred);
+        }
+
+        candidates
+    }
+
+    /// Describes the chain of requirements from the root package down to the given requirement.
+    fn explain(&self, requirement: &Requirement) -> Vec<String> {
+        let mut reasons = vec![];
+        let mut current = requirement;
+
+        loop {
+            let describe = |requirer: String| {
+                format!(
+                    "{} requires {} {}",
+                    requirer,
+                    current.reference.name,
+                    describe_version(&current.reference)
+                )
+            };
+
+            match current
+                .required_by
+                .as_ref()
+                .and_then(|parent| self.selected.get(parent).map(|selected| (parent, selected)))
+            {
+                Some((parent, (parent_version, parent_requirement))) => {
+                    reasons.push(describe(format!("{}@{}", parent, parent_version)));
+                    current = parent_requirement;
+                }
+                None => {
+                    reasons.push(describe(self.root_name.clone()));
+                    break;
+                }
+            }
+        }
+
+        reasons.reverse();
+        reasons
+    }
+}
+
+fn is_satisfied_by(reference: &PackageReference, version: &Version) -> bool {
+    let exact = reference
+        .version
+        .as_deref()
+        .and_then(|v| Version::parse(v).ok());
+
+    match exact {
+        Some(exact) => exact == *version,
+        None => version.pre.is_empty() && reference.matches(&version.to_string()),
+    }
+}
+
+fn describe_version(reference: &PackageReference) -> String {
+    match &reference.version {
+        Some(version) => format!("'{}'", version),
+        None => "the latest stable version".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A published version as `(name, version, dependencies)`.
+    type Published<'a> = (&'a str, &'a str, &'a [(&'a str, &'a str)]);
+
+    fn index(packages: &[Published]) -> RegistryIndex {
+        let mut index = RegistryIndex {
+            packages: BTreeMap::new(),
+        };
+
+        for (name, version, dependencies) in packages {
+            let info = PackageVersionInfo {
+                version: version.to_string(),
+                download_url: String::new(),
+                checksum: None,
+                dependencies: dependencies
+                    .iter()
+                    .map(|(name, range)| (name.to_string(), range.to_string()))
+                    .collect(),
+            };
+            let versions = index.packages.entry(name.to_string()).or_default();
+            versions.push((Version::parse(version).unwrap(), info));
+            versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+        }
+
+        index
+    }
+
+    fn resolve(
+        index: &RegistryIndex,
+        references: &[&str],
+    ) -> Result<BTreeMap<String, String>, ResolutionConflict> {
+        let references = references
+            .iter()
+            .map(|reference| PackageReference::parse(reference).unwrap())
+            .collect();
+
+        Resolver::new(index, "@root/pkg")
+            .resolve(&references)
+            .map(|resolved| {
+                resolved
+                    .into_iter()
+                    .map(|(name, info)| (name, info.version))
+                    .collect()
+            })
+    }
+
+    #[test]
+    fn backtracks_to_an_older_version_when_the_newest_conflicts() {
+        let index = index(&[
+            ("@foo/a", "1.1.0", &[("@foo/c", "^2.0.0")]),
+            ("@foo/a", "1.0.0", &[("@foo/c", "^1.0.0")]),
+            ("@foo/b", "1.0.0", &[("@foo/c", "^1.0.0")]),
+            ("@foo/c", "1.0.0", &[]),
+            ("@foo/c", "2.0.0", &[]),
+        ]);
+
+        let resolved = resolve(&index, &["@foo/a@^1", "@foo/b@^1"]).unwrap();
+
+        assert_eq!(resolved["@foo/a"], "1.0.0");
+        assert_eq!(resolved["@foo/b"], "1.0.0");
+        assert_eq!(resolved["@foo/c"], "1.0.0");
+    }
+
+    #[test]
+    fn ranges_exclude_prereleases_unless_named_exactly() {
+        let index = index(&[
+            ("@foo/a", "1.0.0", &[]),
+            ("@foo/a", "1.1.0-beta.1", &[]),
+        ]);
+
+        assert_eq!(resolve(&index, &["@foo/a@^1"]).unwrap()["@foo/a"], "1.0.0");
+        assert_eq!(resolve(&index, &["@foo/a"]).unwrap()["@foo/a"], "1.0.0");
+        assert_eq!(
+            resolve(&index, &["@foo/a@1.1.0-beta.1"]).unwrap()["@foo/a"],
+            "1.1.0-beta.1"
+        );
+    }
+
+    #[test]
+    fn unpublished_dependencies_only_conflict_when_needed() {
+        let index = index(&[
+            ("@foo/a", "2.0.0", &[("@foo/missing", "^1.0.0")]),
+            ("@foo/a", "1.0.0", &[]),
+        ]);
+
+        assert_eq!(resolve(&index, &["@foo/a@^1"]).unwrap()["@foo/a"], "1.0.0");
+
+        let conflict = resolve(&index, &["@foo/a@^2"]).unwrap_err();
+        assert_eq!(conflict.summary, "Package @foo/missing is not published:");
+    }
+}
@@ -0,0 +1,17 @@
+import { BaseSchema } from '@adonisjs/lucid/schema'
+
+export default class extends BaseSchema {
+  protected tableName = 'package_version'
+
+  async up() {
+    this.schema.alterTable(this.tableName, (table) => {
+      table.string('artifact_checksum').nullable()
+    })
+  }
+
+  async down() {
+    this.schema.alterTable(this.tableName, (table) => {
+      table.dropColumn('artifact_checksum')
+    })
+  }
+}
@@ -0,0 +1,17 @@
+import { BaseSchema } from '@adonisjs/lucid/schema'
+
+export default class extends BaseSchema {
+  protected tableName = 'pending_app'
+
+  async up() {
+    this.schema.alterTable(this.tableName, (table) => {
+      table.integer('token_expires_in').nullable()
+    })
+  }
+
+  async down() {
+    this.schema.alterTable(this.tableName, (table) => {
+      table.dropColumn('token_expires_in')
+    })
+  }
+}
@@ -70,6 +70,8 @@ router
     router.get('/auth/request/:code', [AppController, 'apiPendingAppStatus']).as('auth.request.status');
 
     router.group(() => {
+      router.get('/auth/token', [AppController, 'apiCurrentToken']).as('auth.token.show');
+      router.delete('/auth/token', [AppController, 'apiRevokeCurrentToken']).as('auth.token.revoke');
       router.post('/publish', [PublishController, 'apiCreatePublishJob']).as('package.publish.api.submit');
       router.post('/publish/:jobId/upload', [PublishController, 'apiUploadArchive']).as('package.publish.api.upload');
       router.get('/publish/:jobId', [PublishController, 'apiPublishJobStatus']).as('package.publish.api.status');
@@ -78,6 +80,8 @@ router
     router
       .group(() => {
         router.get('/', [PackageController, 'apiResolvePackage']).as('package.api.resolve');
+        router.get('/versions', [PackageController, 'apiPackageVersions']).as('package.api.versions');
+        router.get('/download', [PackageController, 'apiDownloadPackage']).as('package.api.download');
       })
       .prefix('/package/:scope/:name')
       .where('scope', {